@@ -75,9 +75,24 @@ public unsafe partial struct Color32 : IEquatable<Color32>
     public static readonly Color32 Placeholder = FromRgbaPremultiplied(64, 254, 0, 128);
 
     /// <summary>
-    /// The underlying encoded color.
+    /// The red component of the underlying encoded color.
     /// </summary>
-    private uint _value;
+    private byte _r;
+
+    /// <summary>
+    /// The green component of the underlying encoded color.
+    /// </summary>
+    private byte _g;
+
+    /// <summary>
+    /// The blue component of the underlying encoded color.
+    /// </summary>
+    private byte _b;
+
+    /// <summary>
+    /// The alpha component of the underlying encoded color.
+    /// </summary>
+    private byte _a;
 
     /// <summary>
     /// Parses a color from a hex string.<br/>
@@ -108,7 +123,10 @@ public unsafe partial struct Color32 : IEquatable<Color32>
     internal void Serialize(BincodeSerializer serializer)
     {
         serializer.increase_container_depth();
-        serializer.serialize_u32(_value);
+        serializer.serialize_u8(_r);
+        serializer.serialize_u8(_g);
+        serializer.serialize_u8(_b);
+        serializer.serialize_u8(_a);
         serializer.decrease_container_depth();
     }
 
@@ -119,7 +137,10 @@ public unsafe partial struct Color32 : IEquatable<Color32>
     {
         deserializer.increase_container_depth();
         Color32 obj = default;
-        obj._value = deserializer.deserialize_u32();
+        obj._r = deserializer.deserialize_u8();
+        obj._g = deserializer.deserialize_u8();
+        obj._b = deserializer.deserialize_u8();
+        obj._a = deserializer.deserialize_u8();
         deserializer.decrease_container_depth();
         return obj;
     }
@@ -146,7 +167,10 @@ public unsafe partial struct Color32 : IEquatable<Color32>
     /// <inheritdoc/>
     public bool Equals(Color32 other)
     {
-        if (_value != other._value) return false;
+        if (_r != other._r) return false;
+        if (_g != other._g) return false;
+        if (_b != other._b) return false;
+        if (_a != other._a) return false;
         return true;
     }
 
@@ -156,7 +180,10 @@ public unsafe partial struct Color32 : IEquatable<Color32>
         unchecked
         {
             int value = 7;
-            value = 31 * value + _value.GetHashCode();
+            value = 31 * value + _r.GetHashCode();
+            value = 31 * value + _g.GetHashCode();
+            value = 31 * value + _b.GetHashCode();
+            value = 31 * value + _a.GetHashCode();
             return value;
         }
     }
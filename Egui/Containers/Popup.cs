@@ -106,6 +106,7 @@ public ref partial struct Popup
     private Layout _layout;
     private Frame? _frame;
     private Style? _style;
+    private Func<Style, Style>? _styleModifier;
     private StyleMode _styleMode;
 
 
@@ -128,6 +129,7 @@ public ref partial struct Popup
         _layout = new Layout();
         _frame = null;
         _style = null;
+        _styleModifier = null;
         _styleMode = StyleMode.None;
     }
 
@@ -444,6 +446,18 @@ public ref partial struct Popup
         return this;
     }
 
+    /// <summary>
+    /// Set the style for the popup contents by modifying the current style.
+    /// The modifier may be called any number of times while the popup is shown.
+    /// </summary>
+    public readonly Popup Style(Func<Style, Style> modifier)
+    {
+        var result = this;
+        result._styleModifier = modifier;
+        result._styleMode = StyleMode.ModifyStyle;
+        return result;
+    }
+
     /// <summary>
     /// Show the popup. Returns None if the popup is not open or anchor is <see cref="PopupAnchor.Pointer"/> and there is no pointer.
     /// </summary>
@@ -451,7 +465,8 @@ public ref partial struct Popup
     {
         var ctx = Ctx;
         using var callback = new EguiCallback(ui => addContents(new Ui(ctx, ui)));
-        var (response, setOpen) = EguiMarshal.Call<nuint, SerializablePopup, EguiCallback, (Response?, bool)>(EguiFn.egui_containers_popup_Popup_show, Ctx.Ptr, new SerializablePopup(this), callback);
+        using var popup = new SerializablePopup(this);
+        var (response, setOpen) = EguiMarshal.Call<nuint, SerializablePopup, bool, EguiCallback, (Response?, bool)>(EguiFn.egui_containers_popup_Popup_show, Ctx.Ptr, popup, _openKind == OpenKind.Bool && _open, callback);
 
         if (_openKind == OpenKind.Bool)
         {
@@ -477,7 +492,8 @@ public ref partial struct Popup
         var ctx = Ctx;
         R result = default!;
         using var callback = new EguiCallback(ui => result = addContents(new Ui(ctx, ui)));
-        var (response, setOpen) = EguiMarshal.Call<nuint, SerializablePopup, EguiCallback, (Response?, bool)>(EguiFn.egui_containers_popup_Popup_show, Ctx.Ptr, new SerializablePopup(this), callback);
+        using var popup = new SerializablePopup(this);
+        var (response, setOpen) = EguiMarshal.Call<nuint, SerializablePopup, bool, EguiCallback, (Response?, bool)>(EguiFn.egui_containers_popup_Popup_show, Ctx.Ptr, popup, _openKind == OpenKind.Bool && _open, callback);
 
         if (_openKind == OpenKind.Bool)
         {
@@ -506,7 +522,8 @@ public ref partial struct Popup
         var ctx = Ctx;
         R result = default!;
         using var callback = new EguiCallback(ui => result = addContents(new Ui(ctx, ui)));
-        var (response, setOpen) = EguiMarshal.Call<nuint, SerializablePopup, LayerId, Id, EguiCallback, (Response?, bool)>(EguiFn.egui_containers_tooltip_Tooltip_show, Ctx.Ptr, new SerializablePopup(this), parentLayer, parentId, callback);
+        using var popup = new SerializablePopup(this);
+        var (response, setOpen) = EguiMarshal.Call<nuint, SerializablePopup, LayerId, Id, EguiCallback, (Response?, bool)>(EguiFn.egui_containers_tooltip_Tooltip_show, Ctx.Ptr, popup, parentLayer, parentId, callback);
 
         if (_openKind == OpenKind.Bool)
         {
@@ -531,7 +548,8 @@ public ref partial struct Popup
     {
         None,
         MenuStyle,
-        OverrideStyle
+        OverrideStyle,
+        ModifyStyle
     }
 
     private enum OpenKind
@@ -543,9 +561,10 @@ public ref partial struct Popup
     }
 
     /// <summary>
-    /// Helper struct for serializing popups
+    /// Helper struct for serializing popups. Must be disposed after the call
+    /// that it is passed to, so that the style modifier is unregistered.
     /// </summary>
-    private struct SerializablePopup
+    private struct SerializablePopup : IDisposable
     {
         public Egui.Id Id;
         public Egui.Containers.PopupAnchor Anchor;
@@ -565,6 +584,7 @@ public ref partial struct Popup
         public Egui.Containers.Frame? Frame;
         public Egui.Style? Style;
         public bool MenuStyle;
+        public EguiClosure? StyleModifier;
 
         public SerializablePopup(Popup popup)
         {
@@ -586,6 +606,13 @@ public ref partial struct Popup
             Frame = popup._frame;
             Style = popup._style;
             MenuStyle = popup._styleMode == StyleMode.MenuStyle;
+            StyleModifier = popup._styleMode == StyleMode.ModifyStyle ? EguiClosure.Create(popup._styleModifier!) : null;
+        }
+
+        /// <inheritdoc/>
+        public readonly void Dispose()
+        {
+            ((IDisposable?)StyleModifier)?.Dispose();
         }
 
         internal static void Serialize(BincodeSerializer serializer, SerializablePopup value) => value.Serialize(serializer);
@@ -611,6 +638,7 @@ public ref partial struct Popup
             Egui.TraitHelpers.serialize_option_Frame(Frame, serializer);
             Egui.TraitHelpers.serialize_option_Style(Style, serializer);
             serializer.serialize_bool(MenuStyle);
+            serialize_option_EguiClosure(StyleModifier, serializer);
             serializer.decrease_container_depth();
         }
 
@@ -632,6 +660,19 @@ public ref partial struct Popup
             }
         }
 
+        private static void serialize_option_EguiClosure(EguiClosure? value, BincodeSerializer serializer)
+        {
+            if (value is not null)
+            {
+                serializer.serialize_option_tag(true);
+                EguiClosure.Serialize(serializer, value.Value);
+            }
+            else
+            {
+                serializer.serialize_option_tag(false);
+            }
+        }
+
         private static void serialize_option_UiStackInfo(Egui.UiStackInfo? value, BincodeSerializer serializer)
         {
             if (value is not null)
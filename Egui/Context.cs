@@ -1,3 +1,4 @@
+using System.Collections.Immutable;
 using System.Runtime.CompilerServices;
 using System.Runtime.InteropServices;
 using Egui.Util;
@@ -156,6 +157,26 @@ public sealed partial class Context : EguiObject
     /// </summary>
     public Painter LayerPainter(LayerId layerId) => new Painter(this, EguiMarshal.Call<nuint, LayerId, EguiHandle>(EguiFn.egui_context_Context_layer_painter, Ptr, layerId));
 
+    /// <summary>
+    /// For integrations: this callback will be called when an egui user calls <see cref="RequestRepaint"/> or <see cref="RequestRepaintAfter"/>.<br/>
+    /// 
+    /// This lets you wake up a sleeping UI thread.<br/>
+    /// 
+    /// Note that only one callback can be set. Any new call overrides the previous callback.
+    /// The callback may be invoked from any thread. Exceptions that it throws are rethrown by the
+    /// call which requested the repaint, or logged if the repaint was requested by Rust outside of any call.
+    /// </summary>
+    public void SetRequestRepaintCallback(Action<RequestRepaintInfo> callback)
+    {
+        using var closure = EguiClosure.Create<(ViewportId, double, ulong)>(x => callback(new RequestRepaintInfo
+        {
+            ViewportId = x.Item1,
+            Delay = x.Item2 < Duration.MaxValue.TotalSeconds ? Duration.FromSeconds(x.Item2) : Duration.MaxValue,
+            CurrentCumulativePassNr = x.Item3
+        }));
+        EguiMarshal.Call<nuint, EguiClosure>(EguiFn.egui_context_Context_set_request_repaint_callback, Ptr, closure);
+    }
+
     /// <summary>
     /// Run the ui code for one frame.<br/>
     /// At most <see cref="Options.MaxPasses"/>  calls will be issued to <paramref name="runUi"/>, and only on the rare occasion that <see cref="RequestDiscard"/> is called. Usually, it will only be called once.<br/>
@@ -168,6 +189,25 @@ public sealed partial class Context : EguiObject
         return EguiMarshal.Call<nuint, RawInput, EguiCallback, FullOutput>(EguiFn.egui_context_Context_run, Ptr, input, callback);
     }
 
+    /// <summary>
+    /// An alternative to calling <see cref="Run(RawInput, Action{Context})"/>.<br/>
+    /// 
+    /// It is the responsibility of the caller to call <see cref="EndPass"/> from the same thread.
+    /// Until then, other threads may not begin a pass on this context.
+    /// </summary>
+    public void BeginPass(RawInput newInput)
+    {
+        EguiMarshal.Call<nuint, RawInput>(EguiFn.egui_context_Context_begin_pass, Ptr, newInput);
+    }
+
+    /// <summary>
+    /// Call at the end of each frame if you called <see cref="BeginPass"/>.
+    /// </summary>
+    public FullOutput EndPass()
+    {
+        return EguiMarshal.Call<nuint, FullOutput>(EguiFn.egui_context_Context_end_pass, Ptr);
+    }
+
     /// <summary>
     /// Like <see cref="Run(RawInput, Action{Context})"/>, but reads input and
     /// writes output to an <see cref="EguiFfi"/> object.
@@ -180,6 +220,64 @@ public sealed partial class Context : EguiObject
         EguiMarshal.Call(EguiFn.egui_context_Context_run_ffi, Ptr, ffi.Pointer, callback);
     }
 
+    /// <summary>
+    /// Show a deferred viewport, creating a new native window, if possible.<br/>
+    ///
+    /// The given id must be unique for each viewport.<br/>
+    ///
+    /// You need to call this each pass when the child viewport should exist.<br/>
+    ///
+    /// The given callback will be called whenever the child viewport needs repainting.
+    /// When the context is run with <see cref="RunFfi"/>, this happens whenever the host
+    /// supplies input for the viewport.<br/>
+    ///
+    /// If <see cref="EmbedViewports"/> is <c>true</c> (e.g. if the current egui
+    /// backend does not support multiple viewports), the given callback
+    /// will be called immediately, embedding the new viewport in the current one.
+    /// You can check this with the <see cref="ViewportClass"/> given in the callback.
+    /// If you find <see cref="ViewportClass.Embedded"/>, you need to create a new <see cref="Window"/> for your content.
+    /// </summary>
+    public void ShowViewportDeferred(ViewportId newViewportId, ViewportBuilder viewportBuilder, Action<Context, ViewportClass> viewportUiCb)
+    {
+        using var closure = EguiClosure.Create<ViewportClass>(x => viewportUiCb(this, x));
+        EguiMarshal.Call<nuint, ViewportId, ViewportBuilder, EguiClosure>(EguiFn.egui_context_Context_show_viewport_deferred, Ptr, newViewportId, viewportBuilder, closure);
+    }
+
+    /// <summary>
+    /// Show an immediate viewport, creating a new native window, if possible.<br/>
+    ///
+    /// The given id must be unique for each viewport.<br/>
+    ///
+    /// You need to call this each pass when the child viewport should exist.<br/>
+    ///
+    /// The given ui function will be called immediately.
+    /// When the context is run with <see cref="RunFfi"/>, the child viewport is run as a separate pass,
+    /// and its output is passed to the host alongside that of its parent.<br/>
+    ///
+    /// If <see cref="EmbedViewports"/> is <c>true</c> (e.g. if the current egui
+    /// backend does not support multiple viewports), the given callback
+    /// will be called immediately, embedding the new viewport in the current one.
+    /// You can check this with the <see cref="ViewportClass"/> given in the callback.
+    /// If you find <see cref="ViewportClass.Embedded"/>, you need to create a new <see cref="Window"/> for your content.
+    /// </summary>
+    public void ShowViewportImmediate(ViewportId newViewportId, ViewportBuilder builder, Action<Context, ViewportClass> viewportUiCb)
+    {
+        ShowViewportImmediate(newViewportId, builder, (ctx, viewportClass) =>
+        {
+            viewportUiCb(ctx, viewportClass);
+            return false;
+        });
+    }
+
+    /// <inheritdoc cref="ShowViewportImmediate(ViewportId, ViewportBuilder, Action{Context, ViewportClass})"/>
+    public unsafe R ShowViewportImmediate<R>(ViewportId newViewportId, ViewportBuilder builder, Func<Context, ViewportClass, R> viewportUiCb)
+    {
+        R result = default!;
+        using var callback = new EguiCallback(classPtr => result = viewportUiCb(this, (ViewportClass)(*(uint*)classPtr)));
+        EguiMarshal.Call<nuint, ViewportId, ViewportBuilder, EguiCallback>(EguiFn.egui_context_Context_show_viewport_immediate, Ptr, newViewportId, builder, callback);
+        return result;
+    }
+
     /// <summary>
     /// Mutate the currently active <see cref="Egui.Style"/> used by all subsequent windows, panels etc. Use <see cref="AllStylesMut"/> to mutate both dark and light mode styles.
     /// </summary>
@@ -285,6 +383,14 @@ public sealed partial class Context : EguiObject
         }
     }
 
+    /// <summary>
+    /// Replaces all shapes on one layer of <see cref="GraphicLayers"/>, leaving the other layers untouched.
+    /// </summary>
+    public void SetLayerShapes(LayerId layerId, ImmutableArray<ClippedShape> shapes)
+    {
+        EguiMarshal.Call<nuint, LayerId, ImmutableArray<ClippedShape>>(EguiFn.egui_context_Context_graphics_set_layer, Ptr, layerId, shapes);
+    }
+
     /// <inheritdoc cref="Fonts"/>
     public R Fonts<R>(Func<Fonts, R> reader)
     {
@@ -339,6 +445,26 @@ public sealed partial class Context : EguiObject
         }
     }
 
+    /// <summary>
+    /// Replaces a single field of <see cref="InputState"/>, without copying the rest of the state.
+    /// </summary>
+    /// <param name="field">The name of the field, such as <c>nameof(InputState.Focused)</c>.</param>
+    /// <param name="value">The new value. This must have the same type as the field.</param>
+    public void SetInputField<T>(string field, T value)
+    {
+        EguiMarshal.Call<nuint, string, ImmutableArray<byte>>(EguiFn.egui_context_Context_input_patch, Ptr, field, EguiMarshal.SerializeField(value));
+    }
+
+    /// <summary>
+    /// Replaces a single field of <see cref="Egui.Options"/>, without copying the rest of the options.
+    /// </summary>
+    /// <param name="field">The name of the field, such as <c>nameof(Options.ZoomFactor)</c>.</param>
+    /// <param name="value">The new value. This must have the same type as the field.</param>
+    public void SetOption<T>(string field, T value)
+    {
+        EguiMarshal.Call<nuint, string, ImmutableArray<byte>>(EguiFn.egui_context_Context_options_patch, Ptr, field, EguiMarshal.SerializeField(value));
+    }
+
     /// <summary>
     /// Read-only access to <see cref="InputState"/>. 
     /// </summary>
@@ -423,6 +549,16 @@ public sealed partial class Context : EguiObject
         }
     }
 
+    /// <summary>
+    /// Replaces a single field of <see cref="Egui.Epaint.TessellationOptions"/>, without copying the rest of the options.
+    /// </summary>
+    /// <param name="field">The name of the field, such as <c>nameof(TessellationOptions.Feathering)</c>.</param>
+    /// <param name="value">The new value. This must have the same type as the field.</param>
+    public void SetTessellationOption<T>(string field, T value)
+    {
+        EguiMarshal.Call<nuint, string, ImmutableArray<byte>>(EguiFn.egui_context_Context_tessellation_options_patch, Ptr, field, EguiMarshal.SerializeField(value));
+    }
+
     /// <summary>
     /// This will create a <see cref="InputState"/> if there is no input state for that viewport
     /// </summary>
@@ -442,6 +578,24 @@ public sealed partial class Context : EguiObject
         return reader(input);
     }
 
+    /// <summary>
+    /// Read the state of the current viewport.
+    /// </summary>
+    public ViewportState Viewport()
+    {
+        var (viewportClass, builder, input, used) = EguiMarshal.Call<nuint, (ViewportClass, ViewportBuilder, InputState, bool)>(EguiFn.egui_context_Context_viewport, Ptr);
+        return new ViewportState { Class = viewportClass, Builder = builder, Input = input, Used = used };
+    }
+
+    /// <summary>
+    /// Read the state of a specific current viewport.
+    /// </summary>
+    public ViewportState ViewportFor(ViewportId viewportId)
+    {
+        var (viewportClass, builder, input, used) = EguiMarshal.Call<nuint, ViewportId, (ViewportClass, ViewportBuilder, InputState, bool)>(EguiFn.egui_context_Context_viewport_for, Ptr, viewportId);
+        return new ViewportState { Class = viewportClass, Builder = builder, Input = input, Used = used };
+    }
+
     /// <summary>
     /// This will create a <see cref="InputState"/> if there is no input state for that viewport
     /// </summary>
@@ -468,6 +622,18 @@ public sealed partial class Context : EguiObject
         }
     }
 
+    /// <summary>
+    /// Replaces a single field of the <see cref="InputState"/> for a viewport, without copying the rest of the state.
+    /// This will create a <see cref="InputState"/> if there is no input state for that viewport.
+    /// </summary>
+    /// <param name="id">The viewport whose input should be modified.</param>
+    /// <param name="field">The name of the field, such as <c>nameof(InputState.Focused)</c>.</param>
+    /// <param name="value">The new value. This must have the same type as the field.</param>
+    public void SetInputFieldFor<T>(ViewportId id, string field, T value)
+    {
+        EguiMarshal.Call<nuint, ViewportId, string, ImmutableArray<byte>>(EguiFn.egui_context_Context_input_patch_for, Ptr, id, field, EguiMarshal.SerializeField(value));
+    }
+
     /// <summary>
     /// Read-only access to <see cref="Memory"/>. 
     /// </summary>
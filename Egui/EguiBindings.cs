@@ -7,7 +7,24 @@
     {
         static EguiBindings()
         {
+            CheckAbi();
             egui_init();
         }
+
+        /// <summary>
+        /// Ensures that the loaded native library was generated from the same bindings as this assembly.
+        /// </summary>
+        private static unsafe void CheckAbi()
+        {
+            var abi = egui_abi_info();
+            if (abi.fn_count != (uint)Enum.GetValues<EguiFn>().Length
+                || abi.fn_names_hash != EGUI_FN_NAMES_HASH
+                || abi.schema_hash != EGUI_SCHEMA_HASH)
+            {
+                var crateVersion = System.Text.Encoding.UTF8.GetString(abi.crate_version.ptr, (int)abi.crate_version.len);
+                var eguiVersion = System.Text.Encoding.UTF8.GetString(abi.egui_version.ptr, (int)abi.egui_version.len);
+                throw new InvalidOperationException($"The loaded egui_net library (version {crateVersion}, egui {eguiVersion}) does not match this version of Egui.NET");
+            }
+        }
     }
 }
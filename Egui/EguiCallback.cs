@@ -5,7 +5,8 @@ using System.Runtime.InteropServices;
 namespace Egui;
 
 /// <summary>
-/// A callback that may be passed to unmanaged code.
+/// A callback that may be passed to unmanaged code. The callback is registered with Rust
+/// when created, and arguments refer to it by id until it is disposed.
 /// </summary>
 internal unsafe partial struct EguiCallback : IDisposable
 {
@@ -23,13 +24,22 @@ internal unsafe partial struct EguiCallback : IDisposable
     {
         func = &InvokeCallback;
         data = (void*)(nint)GCHandle.Alloc(callback);
+        id = EguiBindings.egui_callback_register(func, data);
     }
 
     /// <inheritdoc/>
     void IDisposable.Dispose()
     {
+        EguiBindings.egui_callback_unregister(id);
         GCHandle.FromIntPtr((nint)data).Free();
+    }
 
+    /// <summary>
+    /// Rethrows the exception that caused a callback to fail, if any.
+    /// This is used when Rust reports <see cref="EguiErrorKind.HostError"/>.
+    /// </summary>
+    internal static void RethrowLastException()
+    {
         if (_lastException is not null)
         {
             var last = _lastException;
@@ -39,21 +49,34 @@ internal unsafe partial struct EguiCallback : IDisposable
     }
 
     /// <summary>
-    /// Invokes a C# callback.
+    /// Stores an exception thrown by a callback or closure, so that it can be rethrown
+    /// once Rust reports <see cref="EguiErrorKind.HostError"/> for the enclosing call.
+    /// </summary>
+    /// <param name="e">The exception that was thrown.</param>
+    internal static void CaptureException(Exception e)
+    {
+        _lastException = ExceptionDispatchInfo.Capture(e);
+    }
+
+    /// <summary>
+    /// Invokes a C# callback. If the callback throws, the exception is stored and
+    /// Rust is told to abandon the callback so that the error can be reported.
     /// </summary>
     /// <param name="callback">A GC handle to the callback that should be invoked.</param>
     /// <param name="data">The data to provide to the callback.</param>
     [UnmanagedCallersOnly(CallConvs = [typeof(CallConvCdecl)])]
-    private static void InvokeCallback(void* argument, void* data)
+    private static EguiCallbackStatus InvokeCallback(void* argument, void* data)
     {
         try
         {
             var action = (Action<nuint>)GCHandle.FromIntPtr((nint)data).Target!;
             action((nuint)argument);
+            return EguiCallbackStatus.Completed;
         }
         catch (Exception e)
         {
-            _lastException = ExceptionDispatchInfo.Capture(e);
+            CaptureException(e);
+            return EguiCallbackStatus.Failed;
         }
     }
 
@@ -64,8 +87,7 @@ internal unsafe partial struct EguiCallback : IDisposable
     internal static void Serialize(BincodeSerializer serializer, EguiCallback obj)
     {
         serializer.increase_container_depth();
-        serializer.serialize_u64((ulong)obj.func);
-        serializer.serialize_u64((ulong)obj.data);
+        serializer.serialize_u64(obj.id);
         serializer.decrease_container_depth();
     }
 
@@ -78,8 +100,7 @@ internal unsafe partial struct EguiCallback : IDisposable
     {
         deserializer.increase_container_depth();
         EguiCallback obj = default;
-        obj.func = (delegate* unmanaged[Cdecl]<void*, void*, void>)deserializer.deserialize_u64();
-        obj.data = (void*)deserializer.deserialize_u64();
+        obj.id = deserializer.deserialize_u64();
         deserializer.decrease_container_depth();
         return obj;
     }
@@ -0,0 +1,185 @@
+using System.Runtime.CompilerServices;
+using System.Runtime.InteropServices;
+
+namespace Egui;
+
+/// <summary>
+/// A callback that may be owned by unmanaged code. Unlike <see cref="EguiCallback"/>,
+/// the closure remains alive after the call that provides it returns, and is released
+/// by Rust once it is no longer needed. It may be invoked from any thread.<br/>
+///
+/// The closure is registered with Rust when created, and arguments refer to it by id.
+/// It must be disposed once the call that provides it returns, whether or not the call
+/// succeeded. If Rust kept no reference to the closure, it is released immediately.
+/// </summary>
+internal unsafe partial struct EguiClosure : IDisposable
+{
+    /// <summary>
+    /// Creates a new closure which ignores its result.
+    /// </summary>
+    /// <param name="callback">The callback to invoke.</param>
+    public static EguiClosure Create<A>(Action<A> callback)
+    {
+        return Create(new ActionTarget<A>(callback));
+    }
+
+    /// <summary>
+    /// Creates a new closure which returns a value to Rust.
+    /// </summary>
+    /// <param name="callback">The callback to invoke.</param>
+    public static EguiClosure Create<A, R>(Func<A, R> callback)
+    {
+        return Create(new FuncTarget<A, R>(callback));
+    }
+
+    /// <summary>
+    /// Creates a new closure that invokes the given target.
+    /// </summary>
+    /// <param name="target">The target to invoke.</param>
+    private static EguiClosure Create(ClosureTarget target)
+    {
+        var result = new EguiClosure
+        {
+            func = &InvokeClosure,
+            data = (void*)(nint)GCHandle.Alloc(target),
+            release = &ReleaseClosure
+        };
+        result.id = EguiBindings.egui_closure_register(result.func, result.data, result.release);
+        return result;
+    }
+
+    /// <inheritdoc/>
+    void IDisposable.Dispose()
+    {
+        EguiBindings.egui_closure_unregister(id);
+    }
+
+    /// <summary>
+    /// Invokes a C# closure. If the closure throws, the exception is stored and Rust is told
+    /// that the closure failed, so that the error is reported by the enclosing call.
+    /// </summary>
+    /// <param name="argument">The serialized closure arguments.</param>
+    /// <param name="ret">The location to which the result should be written.</param>
+    /// <param name="data">A GC handle to the closure target.</param>
+    [UnmanagedCallersOnly(CallConvs = [typeof(CallConvCdecl)])]
+    private static EguiCallbackStatus InvokeClosure(EguiSliceU8 argument, void* ret, void* data)
+    {
+        try
+        {
+            var target = (ClosureTarget)GCHandle.FromIntPtr((nint)data).Target!;
+            target.Invoke(argument, ret);
+            return EguiCallbackStatus.Completed;
+        }
+        catch (Exception e)
+        {
+            EguiCallback.CaptureException(e);
+            return EguiCallbackStatus.Failed;
+        }
+    }
+
+    /// <summary>
+    /// Frees a C# closure.
+    /// </summary>
+    /// <param name="data">A GC handle to the closure target.</param>
+    [UnmanagedCallersOnly(CallConvs = [typeof(CallConvCdecl)])]
+    private static void ReleaseClosure(void* data)
+    {
+        GCHandle.FromIntPtr((nint)data).Free();
+    }
+
+    /// <summary>
+    /// Serializes an instance of this value.
+    /// </summary>
+    /// <param name="serializer">The serializer to use.</param>
+    internal static void Serialize(BincodeSerializer serializer, EguiClosure obj)
+    {
+        serializer.increase_container_depth();
+        serializer.serialize_u64(obj.id);
+        serializer.decrease_container_depth();
+    }
+
+    /// <summary>
+    /// Deserializes an instance of this value.
+    /// </summary>
+    /// <param name="deserializer">The deserializer to use.</param>
+    /// <returns>The object that was deserialized.</returns>
+    internal static EguiClosure Deserialize(BincodeDeserializer deserializer)
+    {
+        deserializer.increase_container_depth();
+        EguiClosure obj = default;
+        obj.id = deserializer.deserialize_u64();
+        deserializer.decrease_container_depth();
+        return obj;
+    }
+
+    /// <summary>
+    /// The managed object referenced by a closure.
+    /// </summary>
+    private abstract class ClosureTarget
+    {
+        /// <summary>
+        /// Decodes the arguments, invokes the callback, and reports the result.
+        /// </summary>
+        /// <param name="argument">The serialized closure arguments.</param>
+        /// <param name="ret">The location to which the result should be written.</param>
+        public abstract void Invoke(EguiSliceU8 argument, void* ret);
+    }
+
+    /// <summary>
+    /// A closure target which reports no result.
+    /// </summary>
+    private sealed class ActionTarget<A> : ClosureTarget
+    {
+        /// <summary>
+        /// The callback to invoke.
+        /// </summary>
+        private readonly Action<A> _callback;
+
+        /// <summary>
+        /// Creates a new target for the given callback.
+        /// </summary>
+        public ActionTarget(Action<A> callback)
+        {
+            _callback = callback;
+        }
+
+        /// <inheritdoc/>
+        public override void Invoke(EguiSliceU8 argument, void* ret)
+        {
+            _callback(EguiMarshal.DeserializeValue<A>(argument));
+        }
+    }
+
+    /// <summary>
+    /// A closure target which reports a result.
+    /// </summary>
+    private sealed class FuncTarget<A, R> : ClosureTarget
+    {
+        /// <summary>
+        /// The callback to invoke.
+        /// </summary>
+        private readonly Func<A, R> _callback;
+
+        /// <summary>
+        /// Creates a new target for the given callback.
+        /// </summary>
+        public FuncTarget(Func<A, R> callback)
+        {
+            _callback = callback;
+        }
+
+        /// <inheritdoc/>
+        public override void Invoke(EguiSliceU8 argument, void* ret)
+        {
+            var bytes = EguiMarshal.SerializeValue(_callback(EguiMarshal.DeserializeValue<A>(argument)));
+            fixed (byte* bytePtr = bytes)
+            {
+                EguiBindings.egui_closure_return(ret, new EguiSliceU8
+                {
+                    ptr = bytePtr,
+                    len = (nuint)bytes.Length
+                });
+            }
+        }
+    }
+}
@@ -18,4 +18,123 @@ public unsafe sealed class EguiFfi : EguiObject
     /// Creates a new FFI object.
     /// </summary>
     public EguiFfi() : base(EguiMarshal.Call<EguiHandle>(EguiFn.egui_EguiFfi_new)) { }
+
+    /// <summary>
+    /// The layout in which <see cref="Context.RunFfi"/> stores tessellated meshes.
+    /// Defaults to <see cref="FfiMeshLayout.Separate"/>.
+    /// </summary>
+    public FfiMeshLayout MeshLayout
+    {
+        get => _meshLayout;
+        set
+        {
+            EguiMarshal.Call(EguiFn.egui_EguiFfi_set_mesh_layout, Ptr, value);
+            _meshLayout = value;
+        }
+    }
+
+    /// <summary>
+    /// The conversions which <see cref="Context.RunFfi"/> applies to tessellated meshes.
+    /// By default, meshes are left as produced by <c>egui</c>.
+    /// </summary>
+    public FfiOutputFormat OutputFormat
+    {
+        get => _outputFormat;
+        set
+        {
+            EguiMarshal.Call(EguiFn.egui_EguiFfi_set_output_format, Ptr, value.PhysicalPixels, value.LinearColors, value.U16Indices, value.IntegerScissorRects);
+            _outputFormat = value;
+        }
+    }
+
+    /// <summary>
+    /// Whether <see cref="Context.RunFfi"/> tessellates the output into meshes, or stores
+    /// the shapes themselves. Defaults to <see cref="FfiPaintMode.Meshes"/>.
+    /// </summary>
+    public FfiPaintMode PaintMode
+    {
+        get => _paintMode;
+        set
+        {
+            EguiMarshal.Call(EguiFn.egui_EguiFfi_set_paint_mode, Ptr, value);
+            _paintMode = value;
+        }
+    }
+
+    /// <summary>
+    /// The backing field for <see cref="MeshLayout"/>.
+    /// </summary>
+    private FfiMeshLayout _meshLayout;
+
+    /// <summary>
+    /// The backing field for <see cref="OutputFormat"/>.
+    /// </summary>
+    private FfiOutputFormat _outputFormat;
+
+    /// <summary>
+    /// The backing field for <see cref="PaintMode"/>.
+    /// </summary>
+    private FfiPaintMode _paintMode;
+}
+
+/// <summary>
+/// Conversions which <see cref="Context.RunFfi"/> applies to tessellated meshes, so that
+/// hosts can upload them without processing each vertex.
+/// </summary>
+public struct FfiOutputFormat
+{
+    /// <summary>
+    /// Whether vertex positions and clip rectangles are given in physical pixels rather than points.
+    /// </summary>
+    public bool PhysicalPixels;
+
+    /// <summary>
+    /// Whether vertex colors are converted from gamma space to linear space.
+    /// </summary>
+    public bool LinearColors;
+
+    /// <summary>
+    /// Whether indices are stored as 16-bit values. Large meshes are divided, and draw commands
+    /// split, so that every mesh or command addresses at most 65536 vertices.
+    /// </summary>
+    public bool U16Indices;
+
+    /// <summary>
+    /// Whether clip rectangles are converted to physical pixels, rounded to whole pixels,
+    /// and clamped to the screen, so that they may be used directly as scissor rectangles.
+    /// </summary>
+    public bool IntegerScissorRects;
+}
+
+/// <summary>
+/// Determines how <see cref="Context.RunFfi"/> stores the tessellated meshes in an <see cref="EguiFfi"/>.
+/// </summary>
+public enum FfiMeshLayout
+{
+    /// <summary>
+    /// Each mesh has its own vertex and index buffers.
+    /// </summary>
+    Separate,
+    /// <summary>
+    /// All meshes share one vertex buffer and one index buffer, and are drawn by a list
+    /// of draw commands. Adjacent meshes with the same texture and clip rectangle are
+    /// merged into a single command.
+    /// </summary>
+    Contiguous
+}
+
+/// <summary>
+/// Determines what <see cref="Context.RunFfi"/> stores in an <see cref="EguiFfi"/> for painting.
+/// </summary>
+public enum FfiPaintMode
+{
+    /// <summary>
+    /// The shapes are tessellated into meshes.
+    /// </summary>
+    Meshes,
+    /// <summary>
+    /// The clipped shapes are stored without tessellation, so that hosts can draw circles,
+    /// rectangles, paths and text natively. Text is given as a list of positioned glyphs.
+    /// </summary>
+    Shapes
 }
\ No newline at end of file
@@ -124,6 +124,7 @@ internal static class EguiMarshal
                 });
 
                 AssertSuccess(result);
+                EguiBindings.egui_release_result(result.return_value);
             }
         }
     }
@@ -159,8 +160,15 @@ internal static class EguiMarshal
     private unsafe static R DeserializeResult<R>(EguiInvokeResult result)
     {
         AssertSuccess(result);
-        var deserializer = GetDeserializer(result.return_value);
-        return SerializerCache<R>.Deserialize(deserializer);
+        try
+        {
+            var deserializer = GetDeserializer(result.return_value);
+            return SerializerCache<R>.Deserialize(deserializer);
+        }
+        finally
+        {
+            EguiBindings.egui_release_result(result.return_value);
+        }
     }
 
     [MethodImpl(MethodImplOptions.AggressiveInlining)]
@@ -168,7 +176,22 @@ internal static class EguiMarshal
     {
         if (!result.success)
         {
-            throw new InvalidOperationException(new string((char*)result.return_value.ptr, 0, (int)result.return_value.len / sizeof(char)));
+            var message = new string((char*)result.return_value.ptr, 0, (int)result.return_value.len / sizeof(char));
+            EguiBindings.egui_release_result(result.return_value);
+
+            if (result.error.kind == EguiErrorKind.HostError)
+            {
+                EguiCallback.RethrowLastException();
+            }
+
+            throw result.error.kind switch
+            {
+                EguiErrorKind.Unbound => new NotImplementedException(message),
+                EguiErrorKind.InvalidArguments => new ArgumentException(message),
+                EguiErrorKind.NullPointer => new NullReferenceException(message),
+                EguiErrorKind.DanglingPointer => new ObjectDisposedException(null, message),
+                _ => new InvalidOperationException(message)
+            };
         }
     }
 
@@ -208,6 +231,43 @@ internal static class EguiMarshal
         return _deserializer;
     }
 
+    /// <summary>
+    /// Deserializes a value from unmanaged memory. Unlike <see cref="GetDeserializer"/>,
+    /// this does not reuse any buffers, so it may be used while another call is in progress.
+    /// </summary>
+    /// <param name="data">The serialized value.</param>
+    /// <returns>The value that was deserialized.</returns>
+    internal static T DeserializeValue<T>(EguiSliceU8 data)
+    {
+        var stream = new EguiResultStream();
+        stream.Initialize(data);
+        return SerializerCache<T>.Deserialize(new BincodeDeserializer(stream));
+    }
+
+    /// <summary>
+    /// Serializes a value to a new buffer. Unlike <see cref="GetSerializer"/>,
+    /// this does not reuse any buffers, so it may be used while another call is in progress.
+    /// </summary>
+    /// <param name="value">The value to serialize.</param>
+    /// <returns>The serialized bytes.</returns>
+    internal static byte[] SerializeValue<T>(T value)
+    {
+        var serializer = new BincodeSerializer();
+        SerializerCache<T>.Serialize(serializer, value);
+        return serializer.get_bytes();
+    }
+
+    /// <summary>
+    /// Serializes the new value of a single field, for functions which patch
+    /// part of a larger object in place.
+    /// </summary>
+    /// <param name="value">The value to serialize.</param>
+    /// <returns>The serialized bytes.</returns>
+    internal static ImmutableArray<byte> SerializeField<T>(T value)
+    {
+        return ImmutableArray.Create(SerializeValue(value));
+    }
+
     /// <summary>
     /// Serializers for generic types.
     /// </summary>
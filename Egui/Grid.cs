@@ -9,6 +9,11 @@ public partial struct Grid
     /// </summary>
     private bool? _striped;
 
+    /// <summary>
+    /// Computes the background color of each row.
+    /// </summary>
+    private Func<int, Style, Color32?>? _rowColor;
+
     /// <summary>
     /// If <c>True</c>, add a subtle background color to every other row.<br/>
     /// 
@@ -22,6 +27,19 @@ public partial struct Grid
         return result;
     }
 
+    /// <summary>
+    /// Set a row color function. The function is called with the row index and the
+    /// current style, and may return a background color for that row.<br/>
+    /// 
+    /// This overrides <see cref="Striped"/>.
+    /// </summary>
+    public Grid WithRowColor(Func<int, Style, Color32?> colorPicker)
+    {
+        var result = this;
+        result._rowColor = colorPicker;
+        return result;
+    }
+
     public readonly InnerResponse Show(Ui ui, Action<Ui> addContents)
     {
         Style style = default;
@@ -36,7 +54,8 @@ public partial struct Grid
 
         var ctx = ui.Ctx;
         using var callback = new EguiCallback(ui => addContents(new Ui(ctx, ui)));
-        var response = EguiMarshal.Call<nuint, Grid, EguiCallback, Response>(EguiFn.egui_grid_Grid_show, ui.Ptr, this, callback);
+        using var rowColor = CreateRowColor();
+        var response = EguiMarshal.Call<nuint, Grid, EguiClosure?, EguiCallback, Response>(EguiFn.egui_grid_Grid_show, ui.Ptr, this, rowColor, callback);
 
         if (_striped.HasValue)
         {
@@ -64,7 +83,8 @@ public partial struct Grid
         R result = default!;
         var ctx = ui.Ctx;
         using var callback = new EguiCallback(ui => result = addContents(new Ui(ctx, ui)));
-        var response = EguiMarshal.Call<nuint, Grid, EguiCallback, Response>(EguiFn.egui_grid_Grid_show, ui.Ptr, this, callback);
+        using var rowColor = CreateRowColor();
+        var response = EguiMarshal.Call<nuint, Grid, EguiClosure?, EguiCallback, Response>(EguiFn.egui_grid_Grid_show, ui.Ptr, this, rowColor, callback);
 
         if (_striped.HasValue)
         {
@@ -77,4 +97,18 @@ public partial struct Grid
             Response = response
         };
     }
+
+    /// <summary>
+    /// Creates the closure that Rust should use to compute row colors, if any.
+    /// </summary>
+    private readonly EguiClosure? CreateRowColor()
+    {
+        if (_rowColor is null)
+        {
+            return null;
+        }
+
+        var rowColor = _rowColor;
+        return EguiClosure.Create<(ulong, Style), Color32?>(x => rowColor((int)x.Item1, x.Item2));
+    }
 }
\ No newline at end of file
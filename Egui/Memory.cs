@@ -27,6 +27,17 @@ public ref partial struct Memory
         }
     }
 
+    /// <summary>
+    /// Replaces a single field of <see cref="Options"/>, without copying the rest of the options.
+    /// </summary>
+    /// <param name="field">The name of the field, such as <c>nameof(Egui.Options.ZoomFactor)</c>.</param>
+    /// <param name="value">The new value. This must have the same type as the field.</param>
+    public readonly void SetOption<T>(string field, T value)
+    {
+        AssertInitialized();
+        EguiMarshal.Call<nuint, string, ImmutableArray<byte>>(EguiFn.egui_memory_Memory_options_patch, Ptr, field, EguiMarshal.SerializeField(value));
+    }
+
     /// <summary>
     /// An iterator over all layers. Back-to-front, top is last.
     /// </summary>
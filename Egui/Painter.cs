@@ -38,6 +38,19 @@ public sealed partial class Painter : EguiObject
         return EguiMarshal.Call<nuint, Shape, ShapeIdx>(EguiFn.egui_painter_Painter_add, Ptr, shape);
     }
 
+    /// <summary>
+    /// Adds a paint callback which the host draws itself, such as a 3D viewport or video surface.
+    /// When the context is run with <see cref="Context.RunFfi"/>, the callback is passed to the
+    /// host through the <see cref="EguiFfi"/> object in draw order, along with its clip rectangle.
+    /// </summary>
+    /// <param name="rect">The area in which the host should draw.</param>
+    /// <param name="id">An identifier chosen by the host to recognize the callback.</param>
+    [MethodImpl(MethodImplOptions.Synchronized)]
+    public ShapeIdx AddFfiCallback(Rect rect, ulong id)
+    {
+        return EguiMarshal.Call<nuint, Rect, ulong, ShapeIdx>(EguiFn.egui_painter_Painter_add_ffi_callback, Ptr, rect, id);
+    }
+
     /// <summary>
     /// Add many shapes at once.
     /// Calling this once is generally faster than calling <see cref="Add"/> multiple times
@@ -37,7 +37,7 @@ public partial struct Rect
     public EPos2 Center
     {
         readonly get => EguiMarshal.Call<Rect, EPos2>(EguiFn.emath_rect_Rect_center, this);
-        set => this = EguiMarshal.Call<Rect, EPos2, Rect>(EguiFn.emath_rect_Rect_center, this, value);
+        set => this = EguiMarshal.Call<Rect, EPos2, Rect>(EguiFn.emath_rect_Rect_set_center, this, value);
     }
 
     /// <summary>
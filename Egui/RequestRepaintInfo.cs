@@ -0,0 +1,27 @@
+namespace Egui;
+
+/// <summary>
+/// Information given to the backend about when it is time to repaint the ui.<br/>
+///
+/// This is given in the callback set by <see cref="Context.SetRequestRepaintCallback"/>.
+/// </summary>
+public struct RequestRepaintInfo
+{
+    /// <summary>
+    /// This is used to specify what viewport that should repaint.
+    /// </summary>
+    public required ViewportId ViewportId;
+
+    /// <summary>
+    /// Repaint after this duration. If zero, repaint as soon as possible.
+    /// </summary>
+    public required Duration Delay;
+
+    /// <summary>
+    /// The number of fully completed passes, of the entire lifetime of the <see cref="Context"/>.<br/>
+    ///
+    /// This can be compared to <c>Context.CumulativePassNr</c> to see if we've already
+    /// triggered the painting of the next frame.
+    /// </summary>
+    public required ulong CurrentCumulativePassNr;
+}
@@ -1,3 +1,4 @@
+using System.Collections.Immutable;
 using Egui.Util;
 
 namespace Egui;
@@ -79,6 +80,18 @@ public readonly ref partial struct Ui
         }
     }
 
+    /// <summary>
+    /// Replaces a single field of <see cref="Style"/> for this <see cref="Ui"/> and its children,
+    /// without copying the rest of the style.
+    /// </summary>
+    /// <param name="field">The name of the field, such as <c>nameof(Style.AnimationTime)</c>.</param>
+    /// <param name="value">The new value. This must have the same type as the field.</param>
+    public void SetStyleField<T>(string field, T value)
+    {
+        AssertInitialized();
+        EguiMarshal.Call<nuint, string, ImmutableArray<byte>>(EguiFn.egui_ui_Ui_style_patch, Ptr, field, EguiMarshal.SerializeField(value));
+    }
+
     /// <summary>
     /// The current visuals settings of this <see cref="Ui"/> . Short for <c>ui.Style.Visuals</c>.
     /// </summary>
@@ -0,0 +1,30 @@
+namespace Egui.Viewport;
+
+/// <summary>
+/// Per-viewport state, as returned by <see cref="Context.Viewport"/> and <see cref="Context.ViewportFor"/>.
+/// </summary>
+public struct ViewportState
+{
+    /// <summary>
+    /// The type of viewport.<br/>
+    ///
+    /// This will never be <see cref="ViewportClass.Embedded"/>,
+    /// since those don't result in real viewports.
+    /// </summary>
+    public required ViewportClass Class;
+
+    /// <summary>
+    /// The latest delta.
+    /// </summary>
+    public required ViewportBuilder Builder;
+
+    /// <summary>
+    /// The input of the viewport.
+    /// </summary>
+    public required InputState Input;
+
+    /// <summary>
+    /// Has this viewport been updated this pass?
+    /// </summary>
+    public required bool Used;
+}
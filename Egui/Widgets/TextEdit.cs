@@ -9,6 +9,7 @@ public ref partial struct TextEdit : IWidget
     private string? _textImmutable;
     private ref string _textMutable;
     private TextEditInner _inner;
+    private Func<string, float, Egui.Text.LayoutJob>? _layouter;
 
 #pragma warning disable CS8618
     private TextEdit(string textImmutable)
@@ -134,6 +135,21 @@ public ref partial struct TextEdit : IWidget
         return result;
     }
 
+    /// <summary>
+    /// Override how text is being shown inside the <c>TextEdit</c>.<br/>
+    /// 
+    /// This can be used to implement things like syntax highlighting.<br/>
+    /// 
+    /// The layouter is called with the current text and wrap width, and returns the
+    /// <see cref="Egui.Text.LayoutJob"/> that should be used to lay out the text.
+    /// </summary>
+    public readonly TextEdit Layouter(Func<string, float, Egui.Text.LayoutJob> layouter)
+    {
+        var result = this;
+        result._layouter = layouter;
+        return result;
+    }
+
     /// <summary>
     /// If true, hide the letters from view and prevent copying from the field.
     /// </summary>
@@ -357,7 +373,13 @@ public ref partial struct TextEdit : IWidget
         ui.AssertInitialized();
         var mutable = _textImmutable is null;
         var textToSend = mutable ? _textMutable : _textImmutable;
-        var (response, newText) = EguiMarshal.Call<nuint, TextEditInner, string, bool, (Response, string)>(EguiFn.egui_widgets_text_edit_builder_TextEdit_ui, ui.Ptr, _inner, textToSend!, _textImmutable is null);
+
+        var inner = _inner;
+        var layouter = _layouter;
+        using var layouterClosure = layouter is null ? (EguiClosure?)null : EguiClosure.Create<(string, float), Egui.Text.LayoutJob>(x => layouter(x.Item1, x.Item2));
+        inner.Layouter = layouterClosure;
+
+        var (response, newText) = EguiMarshal.Call<nuint, TextEditInner, string, bool, (Response, string)>(EguiFn.egui_widgets_text_edit_builder_TextEdit_ui, ui.Ptr, inner, textToSend!, _textImmutable is null);
         if (mutable)
         {
             _textMutable = newText;
@@ -388,6 +410,7 @@ public ref partial struct TextEdit : IWidget
         public ulong CharLimit;
         public Egui.KeyboardShortcut? ReturnKey;
         public Egui.Color32? BackgroundColor;
+        public EguiClosure? Layouter;
 
         internal static void Serialize(Bincode.BincodeSerializer serializer, TextEditInner value) => value.Serialize(serializer);
 
@@ -415,6 +438,7 @@ public ref partial struct TextEdit : IWidget
             serializer.serialize_u64(CharLimit);
             serialize_option_KeyboardShortcut(ReturnKey, serializer);
             Egui.TraitHelpers.serialize_option_Color32(BackgroundColor, serializer);
+            serialize_option_EguiClosure(Layouter, serializer);
             serializer.decrease_container_depth();
         }
 
@@ -433,6 +457,19 @@ public ref partial struct TextEdit : IWidget
             }
         }
 
+        private static void serialize_option_EguiClosure(EguiClosure? value, Bincode.BincodeSerializer serializer)
+        {
+            if (value is not null)
+            {
+                serializer.serialize_option_tag(true);
+                EguiClosure.Serialize(serializer, value.Value);
+            }
+            else
+            {
+                serializer.serialize_option_tag(false);
+            }
+        }
+
         private static void serialize_option_KeyboardShortcut(Egui.KeyboardShortcut? value, Bincode.BincodeSerializer serializer)
         {
             if (value is not null)
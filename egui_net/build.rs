@@ -43,8 +43,13 @@ fn main() {
         .csharp_class_name("EguiBindings")
         .csharp_class_accessibility("internal")
         .csharp_dll_name("egui_net")
-        .always_included_types(["EguiAnimatedUi", "EguiCallback", "EguiFn", "EguiScrollAreaShowRowsParams", "EguiScrollAreaShowViewportParams", "EguiContextRunTessellateParams"])
-        .csharp_generate_const_filter(|_| true);
+        .always_included_types(["EguiAnimatedUi", "EguiCallback", "EguiCallbackStatus", "EguiClosure", "EguiFn", "EguiScrollAreaShowRowsParams", "EguiScrollAreaShowViewportParams", "EguiContextRunTessellateParams"])
+        .csharp_generate_const_filter(|_| true)
+        .csharp_type_rename(|name| match name.as_str() {
+            "Pos2" => "EPos2".to_string(),
+            "Vec2" => "EVec2".to_string(),
+            _ => name
+        });
 
     for file in get_all_files("src") {
         builder = builder.input_extern_file(file);
@@ -0,0 +1,109 @@
+//! Shared harness for the `egui_net` fuzz targets. Each input runs against a fresh [`Context`]
+//! in the middle of a pass, so that bound functions may be given a live [`Context`] and [`Ui`].
+//! A single host callback and a single host closure, neither of which does anything, are
+//! registered for fuzzed arguments to refer to. Since both are registered first, their ids are
+//! the same for every input, and arguments are decoded through the same id lookup as for a real
+//! host. Any other id must be rejected.
+
+use egui::*;
+use egui_net::*;
+use egui_net::callback::{egui_callback_register, egui_closure_register};
+use egui_net::fuzzing::{release_all, EguiScope};
+use std::ffi::c_void;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{LazyLock, Once};
+
+/// Functions whose contracts cannot be checked by the bindings, and which are never fuzzed.
+const EXCLUDED_FNS: &[EguiFn] = &[
+    // Releases a reference which the host received from a serialized `Response`. Unbalanced calls free the context.
+    EguiFn::egui_context_Context_ref_decrement
+];
+
+/// The function named by the `EGUI_FUZZ_FN` environment variable, if any. When set, only
+/// that function is fuzzed, and no bytes of the input are used to select the function.
+static PINNED_FN: LazyLock<Option<EguiFn>> = LazyLock::new(|| {
+    let name = std::env::var("EGUI_FUZZ_FN").ok()?;
+    let f = EguiFn::ALL.iter().find(|x| format!("{x:?}") == name).unwrap_or_else(|| panic!("Unknown function {name}"));
+    Some(*f)
+});
+
+/// Invokes a bound function with arguments taken from `data`. The first byte selects the live
+/// object, if any, whose address is prepended to the arguments. Unless a function is pinned
+/// with `EGUI_FUZZ_FN`, the next two bytes select the function. Malformed arguments must
+/// only ever produce an error result.
+pub fn fuzz_invoke(data: &[u8]) {
+    let Some((&receiver, data)) = data.split_first() else { return };
+    let (f, args) = match *PINNED_FN {
+        Some(f) => (f, data),
+        None => {
+            let [low, high, args @ ..] = data else { return };
+            (EguiFn::ALL[u16::from_le_bytes([*low, *high]) as usize % EguiFn::ALL.len()], args)
+        }
+    };
+
+    if EXCLUDED_FNS.iter().any(|x| *x as u32 == f as u32) {
+        return;
+    }
+
+    with_live_ui(|ctx, ui| {
+        let mut buffer = match receiver % 3 {
+            0 => Vec::new(),
+            1 => bincode::serialize(&ctx).expect("Failed to encode context"),
+            _ => bincode::serialize(&ui).expect("Failed to encode ui")
+        };
+        buffer.extend_from_slice(args);
+
+        let result = unsafe { egui_invoke(f, EguiSliceU8::from_slice(&buffer)) };
+
+        // Touch the returned bytes, so that an invalid result buffer is detected by the sanitizer.
+        std::hint::black_box(unsafe { result.return_value.to_slice() }.iter().fold(0u8, |a, b| a ^ b));
+        result.release();
+    });
+}
+
+/// Runs `f` in the middle of a pass on a fresh context, passing the addresses of the context
+/// and of a live [`Ui`]. Every handle is released afterward, so that no state carries over
+/// between inputs.
+fn with_live_ui(f: impl FnOnce(usize, usize)) {
+    /// Ensures that the bindings are initialized exactly once.
+    static INIT: Once = Once::new();
+
+    // This replaces the hook installed by libfuzzer, which aborts on every panic,
+    // including those that the bindings catch and report as errors. The callback and
+    // closure are never unregistered, so that their ids are the same for every input.
+    INIT.call_once(|| unsafe {
+        egui_init();
+        egui_callback_register(empty_callback, std::ptr::null());
+        egui_closure_register(empty_closure, std::ptr::null(), empty_release);
+    });
+
+    let handle = EguiHandle::to_heap(Context::default());
+    let ctx = unsafe { &*(handle.ptr() as *const Context) };
+    let mut f = Some(f);
+
+    // Bound functions may leave the pass in a state that egui rejects when it ends.
+    // Such panics are not memory errors, so they are ignored.
+    let _ = catch_unwind(AssertUnwindSafe(|| ctx.run(RawInput::default(), |ctx| {
+        CentralPanel::default().show(ctx, |ui| {
+            let scope = EguiScope::enter(ui);
+            if let Some(f) = f.take() {
+                f(handle.ptr(), scope.ptr());
+            }
+        });
+    })));
+
+    release_all();
+}
+
+/// The host callback that fuzzed arguments may refer to. Completes without doing anything.
+unsafe extern "C" fn empty_callback(_: *const c_void, _: *const c_void) -> EguiCallbackStatus {
+    EguiCallbackStatus::Completed
+}
+
+/// The host closure that fuzzed arguments may refer to. Completes without reporting a result.
+unsafe extern "C" fn empty_closure(_: EguiSliceU8, _: *mut c_void, _: *const c_void) -> EguiCallbackStatus {
+    EguiCallbackStatus::Completed
+}
+
+/// Releases [`empty_closure`], which owns no data.
+unsafe extern "C" fn empty_release(_: *const c_void) {}
@@ -0,0 +1,28 @@
+//! Replays a trace recorded with `egui_trace_begin` against a fresh `egui` context,
+//! and reports every place where the results differ from the recording.
+
+/// Replays the trace file given on the command line.
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("Usage: egui_replay <trace file>");
+        std::process::exit(2);
+    };
+
+    let report = match egui_net::trace::replay(&path) {
+        Ok(report) => report,
+        Err(error) => {
+            eprintln!("Failed to read trace {path}: {error}");
+            std::process::exit(2);
+        }
+    };
+
+    for divergence in &report.divergences {
+        println!("{divergence}");
+    }
+
+    println!("Replayed {} calls with {} divergences", report.calls, report.divergences.len());
+
+    if !report.divergences.is_empty() {
+        std::process::exit(1);
+    }
+}
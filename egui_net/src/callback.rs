@@ -0,0 +1,160 @@
+//! Keeps tables of the host callbacks and closures which may be invoked, so that serialized
+//! arguments refer to them by an opaque id rather than by address. An id that is unknown, or
+//! whose entry has since been unregistered, fails to decode instead of causing an arbitrary call.
+
+use super::*;
+use closure::EguiClosure;
+
+/// The callbacks which are currently registered.
+static CALLBACKS: LazyLock<Mutex<CallbackTable<EguiCallback>>> = LazyLock::new(Default::default);
+
+/// The closures which are currently registered.
+static CLOSURES: LazyLock<Mutex<CallbackTable<Arc<EguiClosure>>>> = LazyLock::new(Default::default);
+
+/// Registers a host callback, returning the id by which serialized arguments should refer
+/// to it. The callback remains valid until it is passed to [`egui_callback_unregister`].
+///
+/// `func` may be called from any thread that invokes a function with the callback as an argument.
+#[no_mangle]
+pub unsafe extern "C" fn egui_callback_register(func: unsafe extern "C" fn(*const c_void, *const c_void) -> EguiCallbackStatus, data: *const c_void) -> u64 {
+    CALLBACKS.lock().unwrap_or_else(PoisonError::into_inner).insert(|id| EguiCallback { func, data, id })
+}
+
+/// Unregisters a host callback, so that its id is no longer accepted. Returns `false`
+/// if the id was unknown or had already been unregistered.
+#[no_mangle]
+pub unsafe extern "C" fn egui_callback_unregister(id: u64) -> bool {
+    CALLBACKS.lock().unwrap_or_else(PoisonError::into_inner).remove(id).is_some()
+}
+
+/// Registers a host closure, returning the id by which serialized arguments should refer to it.
+/// Functions which decode the id may keep the closure alive after they return. `release` is called
+/// exactly once, after [`egui_closure_unregister`] has been called and no such references remain.
+///
+/// `func` and `release` may be called from any thread.
+#[no_mangle]
+pub unsafe extern "C" fn egui_closure_register(func: unsafe extern "C" fn(EguiSliceU8, *mut c_void, *const c_void) -> EguiCallbackStatus, data: *const c_void, release: unsafe extern "C" fn(*const c_void)) -> u64 {
+    CLOSURES.lock().unwrap_or_else(PoisonError::into_inner).insert(|id| Arc::new(EguiClosure { func, data, release, id }))
+}
+
+/// Unregisters a host closure, so that its id is no longer accepted. If Rust holds no other
+/// references to the closure, then it is released before this function returns. Returns `false`
+/// if the id was unknown or had already been unregistered.
+#[no_mangle]
+pub unsafe extern "C" fn egui_closure_unregister(id: u64) -> bool {
+    // The closure is dropped after the table is unlocked, since releasing it calls into the host.
+    let closure = CLOSURES.lock().unwrap_or_else(PoisonError::into_inner).remove(id);
+    closure.is_some()
+}
+
+/// Gets the callback with the given id, if it is registered.
+pub(crate) fn lookup(id: u64) -> Option<EguiCallback> {
+    CALLBACKS.lock().unwrap_or_else(PoisonError::into_inner).get(id).copied()
+}
+
+/// Gets a reference to the closure with the given id, if it is registered.
+pub(crate) fn lookup_closure(id: u64) -> Option<Arc<EguiClosure>> {
+    CLOSURES.lock().unwrap_or_else(PoisonError::into_inner).get(id).cloned()
+}
+
+/// Holds the registered entries of one kind.
+struct CallbackTable<T> {
+    /// The slots, indexed by the low bits of an id.
+    slots: Vec<CallbackSlot<T>>,
+    /// The indices of slots which are not in use.
+    free: Vec<u32>
+}
+
+impl<T> CallbackTable<T> {
+    /// Stores the value created by `f` in a free slot, returning its id.
+    /// `f` is given the id so that the value may record it.
+    fn insert(&mut self, f: impl FnOnce(u64) -> T) -> u64 {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(CallbackSlot { generation: 1, value: None });
+                (self.slots.len() - 1) as u32
+            }
+        };
+
+        let slot = &mut self.slots[index as usize];
+        let id = ((slot.generation as u64) << 32) | index as u64;
+        slot.value = Some(f(id));
+        id
+    }
+
+    /// Removes the entry with the given id, returning it if it was registered.
+    fn remove(&mut self, id: u64) -> Option<T> {
+        let index = id as u32;
+        let slot = self.slots.get_mut(index as usize).filter(|x| x.generation == (id >> 32) as u32)?;
+        let value = slot.value.take()?;
+
+        slot.generation = slot.generation.checked_add(1).unwrap_or(1);
+        self.free.push(index);
+        Some(value)
+    }
+
+    /// Gets the entry with the given id, if it is registered.
+    fn get(&self, id: u64) -> Option<&T> {
+        self.slots.get(id as u32 as usize)
+            .filter(|x| x.generation == (id >> 32) as u32)
+            .and_then(|x| x.value.as_ref())
+    }
+}
+
+impl<T> Default for CallbackTable<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new()
+        }
+    }
+}
+
+/// A single entry in a callback table.
+struct CallbackSlot<T> {
+    /// Incremented whenever the slot is vacated, and stored in the high bits of an id,
+    /// so that ids from earlier registrations are rejected. Never zero.
+    generation: u32,
+    /// The registered value, if the slot is in use.
+    value: Option<T>
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Completes without doing anything.
+    unsafe extern "C" fn empty_callback(_: *const c_void, _: *const c_void) -> EguiCallbackStatus {
+        EguiCallbackStatus::Completed
+    }
+
+    /// Ensures that unknown and stale ids are rejected, both directly and when decoding
+    /// callback or closure arguments.
+    #[test]
+    fn rejects_stale_ids() {
+        let id = unsafe { egui_callback_register(empty_callback, std::ptr::null()) };
+        assert_eq!(lookup(id).map(|x| x.id), Some(id));
+        assert!(unsafe { egui_callback_unregister(id) });
+        assert!(lookup(id).is_none());
+        assert!(!unsafe { egui_callback_unregister(id) });
+
+        let ctx = EguiHandle::to_heap(Context::default());
+        for stale in [id, 0, u64::MAX] {
+            let args = bincode::serialize(&(ctx.ptr, RawInput::default(), stale)).expect("Failed to encode args");
+            let result = unsafe { egui_invoke(EguiFn::egui_context_Context_run, EguiSliceU8::from_slice(&args)) };
+            assert!(!result.success);
+            assert_eq!(result.error.kind, EguiErrorKind::InvalidArguments);
+            result.release();
+        }
+
+        let args = bincode::serialize(&(ctx.ptr, id)).expect("Failed to encode args");
+        let result = unsafe { egui_invoke(EguiFn::egui_context_Context_set_request_repaint_callback, EguiSliceU8::from_slice(&args)) };
+        assert!(!result.success);
+        assert_eq!(result.error.kind, EguiErrorKind::InvalidArguments);
+        result.release();
+
+        assert!(unsafe { egui_drop(ctx) });
+    }
+}
@@ -0,0 +1,262 @@
+//! Ensures that objects which may only be driven by one thread at a time, such as a
+//! [`Context`] that is running a pass, raise an error when another thread uses them.
+//! Separate contexts may be used from separate threads concurrently.
+
+use super::*;
+use std::thread::ThreadId;
+
+/// The objects which are currently claimed, keyed by identity.
+static CLAIMS: LazyLock<Mutex<HashMap<usize, Claim>>> = LazyLock::new(Default::default);
+
+/// Marks an object as in use by the current thread until dropped. Claims are
+/// reentrant, so nested calls on the same thread may claim the same object again.
+pub struct EguiClaim {
+    /// The identity of the claimed object.
+    key: usize
+}
+
+impl EguiClaim {
+    /// Claims the object identified by `key` for the current thread, raising an
+    /// error if another thread has claimed it. `what` names the object in the error.
+    #[track_caller]
+    pub fn acquire(key: usize, what: &str) -> Self {
+        hold(key, what);
+        Self { key }
+    }
+
+    /// Claims the context handle for the current thread.
+    #[track_caller]
+    pub fn context(ctx: &Context) -> Self {
+        Self::acquire(context_key(ctx), "Context")
+    }
+}
+
+impl Drop for EguiClaim {
+    fn drop(&mut self) {
+        release(self.key);
+    }
+}
+
+/// Claims the context for the current thread until [`end_context`] is called, for passes
+/// which span several calls. The claim is released again if `begin` unwinds.
+#[track_caller]
+pub fn begin_context(ctx: &Context, begin: impl FnOnce()) {
+    let claim = EguiClaim::context(ctx);
+    begin();
+    std::mem::forget(claim);
+}
+
+/// Releases a claim taken by [`begin_context`] after calling `end`. Raises an error without
+/// calling `end` if the claim belongs to another thread.
+#[track_caller]
+pub fn end_context<R>(ctx: &Context, end: impl FnOnce() -> R) -> R {
+    let key = context_key(ctx);
+    let owner = CLAIMS.lock().unwrap_or_else(PoisonError::into_inner).get(&key).map(|x| x.thread);
+    if owner.is_some_and(|x| x != std::thread::current().id()) {
+        raise_error(EguiErrorKind::ConcurrentAccess, "Context pass was begun by another thread");
+    }
+
+    let _claim = EguiClaim { key };
+    end()
+}
+
+/// Gets the identity of a context handle, which is the address of its heap allocation.
+fn context_key(ctx: &Context) -> usize {
+    ctx as *const Context as usize
+}
+
+/// Adds a claim on `key` for the current thread.
+#[track_caller]
+fn hold(key: usize, what: &str) {
+    let thread = std::thread::current().id();
+    let mut claims = CLAIMS.lock().unwrap_or_else(PoisonError::into_inner);
+    let claim = claims.entry(key).or_insert(Claim { thread, depth: 0 });
+
+    if claim.thread != thread {
+        drop(claims);
+        raise_error(EguiErrorKind::ConcurrentAccess, format!("{what} is in use by another thread"));
+    }
+
+    claim.depth += 1;
+}
+
+/// Removes one claim on `key`.
+fn release(key: usize) {
+    let mut claims = CLAIMS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(claim) = claims.get_mut(&key) {
+        claim.depth -= 1;
+        if claim.depth == 0 {
+            claims.remove(&key);
+        }
+    }
+}
+
+/// Describes the thread that holds a claim.
+struct Claim {
+    /// The thread which holds the claim.
+    thread: ThreadId,
+    /// The number of times the thread has claimed the object.
+    depth: usize
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Barrier};
+    use std::sync::atomic::Ordering;
+
+    /// The number of threads to use for stress tests.
+    const THREADS: usize = 8;
+
+    /// The number of frames that each thread should run.
+    const FRAMES: usize = 32;
+
+    /// Invokes `f` with serialized `args`, returning the result after releasing its return value.
+    fn invoke(f: EguiFn, args: &impl Serialize) -> EguiInvokeResult {
+        let args = bincode::serialize(args).expect("Failed to encode args");
+        let result = unsafe { egui_invoke(f, EguiSliceU8::from_slice(&args)) };
+        result.release();
+        EguiInvokeResult {
+            return_value: EguiSliceU8::from_slice(&[]),
+            ..result
+        }
+    }
+
+    /// A host callback which is registered until dropped.
+    struct TestCallback(u64);
+
+    impl TestCallback {
+        /// Registers `func`, which will be passed `data`.
+        fn new(func: unsafe extern "C" fn(*const c_void, *const c_void) -> EguiCallbackStatus, data: usize) -> Self {
+            Self(unsafe { callback::egui_callback_register(func, data as *const c_void) })
+        }
+    }
+
+    impl Drop for TestCallback {
+        fn drop(&mut self) {
+            unsafe {
+                callback::egui_callback_unregister(self.0);
+            }
+        }
+    }
+
+    /// Adds a separator to the [`Ui`] that is passed as the argument.
+    unsafe extern "C" fn add_separator(ui: *const c_void, _: *const c_void) -> EguiCallbackStatus {
+        match invoke(EguiFn::egui_ui_Ui_separator, &(ui as usize)).success {
+            true => EguiCallbackStatus::Completed,
+            false => EguiCallbackStatus::Failed
+        }
+    }
+
+    /// Shows a central panel in the context whose address is passed as data.
+    unsafe extern "C" fn show_panel(_: *const c_void, ctx: *const c_void) -> EguiCallbackStatus {
+        match invoke(EguiFn::egui_containers_panel_CentralPanel_show, &(ctx as usize, CentralPanel::default(), TestCallback::new(add_separator, 0).0)).success {
+            true => EguiCallbackStatus::Completed,
+            false => EguiCallbackStatus::Failed
+        }
+    }
+
+    /// Waits twice on the barrier passed as data, holding the pass open in between.
+    unsafe extern "C" fn wait_twice(_: *const c_void, barrier: *const c_void) -> EguiCallbackStatus {
+        let barrier = &*(barrier as *const Barrier);
+        barrier.wait();
+        barrier.wait();
+        EguiCallbackStatus::Completed
+    }
+
+    /// Waits until every thread has entered this callback, counting arrivals with the [`AtomicUsize`]
+    /// passed as data. Fails if the other threads do not arrive in time.
+    unsafe extern "C" fn meet(_: *const c_void, arrived: *const c_void) -> EguiCallbackStatus {
+        let arrived = &*(arrived as *const AtomicUsize);
+        arrived.fetch_add(1, Ordering::SeqCst);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while arrived.load(Ordering::SeqCst) < THREADS {
+            if deadline < Instant::now() {
+                return EguiCallbackStatus::Failed;
+            }
+            std::thread::yield_now();
+        }
+
+        EguiCallbackStatus::Completed
+    }
+
+    /// Ensures that the passes of separate contexts overlap, rather than being serialized,
+    /// by requiring every thread to be inside its pass at once.
+    #[test]
+    fn overlaps_passes() {
+        let arrived = Arc::new(AtomicUsize::new(0));
+        let threads = (0..THREADS).map(|_| {
+            let arrived = arrived.clone();
+            std::thread::spawn(move || {
+                let ctx = EguiHandle::to_heap(Context::default());
+                let result = invoke(EguiFn::egui_context_Context_run, &(ctx.ptr, RawInput::default(), TestCallback::new(meet, Arc::as_ptr(&arrived) as usize).0));
+                assert!(unsafe { egui_drop(ctx) });
+                result.success
+            })
+        }).collect::<Vec<_>>();
+
+        for thread in threads {
+            assert!(thread.join().expect("Context thread panicked"), "Passes of separate contexts did not overlap");
+        }
+    }
+
+    /// Runs many separate contexts in parallel, each of which lays out widgets every frame.
+    #[test]
+    fn runs_contexts_in_parallel() {
+        let threads = (0..THREADS).map(|_| std::thread::spawn(|| {
+            let ctx = EguiHandle::to_heap(Context::default());
+            for _ in 0..FRAMES {
+                let result = invoke(EguiFn::egui_context_Context_run, &(ctx.ptr, RawInput::default(), TestCallback::new(show_panel, ctx.ptr).0));
+                assert!(result.success, "Frame failed with {:?}", result.error.kind);
+            }
+            assert!(unsafe { egui_drop(ctx) });
+        })).collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().expect("Context thread panicked");
+        }
+    }
+
+    /// Ensures that another thread cannot end a pass, and that the pass stays open for its owner.
+    #[test]
+    fn rejects_foreign_end_pass() {
+        let ctx = EguiHandle::to_heap(Context::default());
+        assert!(invoke(EguiFn::egui_context_Context_begin_pass, &(ctx.ptr, RawInput::default())).success);
+
+        let ptr = ctx.ptr;
+        let result = std::thread::spawn(move || invoke(EguiFn::egui_context_Context_end_pass, &ptr)).join().expect("Foreign thread panicked");
+        assert!(!result.success);
+        assert_eq!(result.error.kind, EguiErrorKind::ConcurrentAccess);
+
+        assert!(invoke(EguiFn::egui_containers_panel_CentralPanel_show, &(ctx.ptr, CentralPanel::default(), TestCallback::new(add_separator, 0).0)).success);
+        assert!(invoke(EguiFn::egui_context_Context_end_pass, &ctx.ptr).success);
+        assert!(unsafe { egui_drop(ctx) });
+    }
+
+    /// Ensures that running a context which is mid-pass on another thread raises an error.
+    #[test]
+    fn rejects_shared_context() {
+        let ctx = EguiHandle::to_heap(Context::default());
+        let barrier = Arc::new(Barrier::new(2));
+
+        let owner = {
+            let barrier = barrier.clone();
+            let ptr = ctx.ptr;
+            std::thread::spawn(move || invoke(EguiFn::egui_context_Context_run, &(ptr, RawInput::default(), TestCallback::new(wait_twice, Arc::as_ptr(&barrier) as usize).0)).success)
+        };
+
+        barrier.wait();
+        let result = invoke(EguiFn::egui_context_Context_run, &(ctx.ptr, RawInput::default(), TestCallback::new(show_panel, ctx.ptr).0));
+        barrier.wait();
+
+        assert!(owner.join().expect("Owner thread panicked"));
+        assert!(!result.success);
+        assert_eq!(result.error.kind, EguiErrorKind::ConcurrentAccess);
+
+        let result = invoke(EguiFn::egui_context_Context_run, &(ctx.ptr, RawInput::default(), TestCallback::new(show_panel, ctx.ptr).0));
+        assert!(result.success);
+        assert!(unsafe { egui_drop(ctx) });
+    }
+}
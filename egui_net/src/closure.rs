@@ -0,0 +1,208 @@
+//! Host closures which are owned by Rust, so that they may be stored by `egui`
+//! and invoked after the call that provided them has returned.
+
+use super::*;
+use std::sync::Arc;
+
+/// A host closure whose lifetime is managed by Rust. Unlike [`EguiCallback`], the closure
+/// remains valid until `release` is called, which happens exactly once after the closure has been
+/// unregistered and the last [`EguiOwnedClosure`] referencing it is dropped. Arguments refer to
+/// closures by the id with which they were registered; see [`callback::egui_closure_register`].
+/// The closure may be invoked and released from any thread.
+#[repr(C)]
+pub struct EguiClosure {
+    /// Invokes the closure with serialized arguments. The closure may report a serialized
+    /// result by passing the second argument to [`egui_closure_return`].
+    pub(crate) func: unsafe extern "C" fn(EguiSliceU8, *mut c_void, *const c_void) -> EguiCallbackStatus,
+    /// Data to pass as the final function argument.
+    pub(crate) data: *const c_void,
+    /// Frees the data associated with the closure.
+    pub(crate) release: unsafe extern "C" fn(*const c_void),
+    /// The id with which the closure was registered.
+    pub(crate) id: u64
+}
+
+unsafe impl Send for EguiClosure {}
+unsafe impl Sync for EguiClosure {}
+
+impl Drop for EguiClosure {
+    fn drop(&mut self) {
+        unsafe {
+            (self.release)(self.data);
+        }
+    }
+}
+
+/// Sets the result of a closure invocation. `ret` must be the pointer that was passed
+/// to the closure, and this function may only be called before the closure returns.
+#[no_mangle]
+pub unsafe extern "C" fn egui_closure_return(ret: *mut c_void, value: EguiSliceU8) {
+    *(ret as *mut Option<Vec<u8>>) = Some(value.to_slice().to_vec());
+}
+
+/// A reference-counted handle to an [`EguiClosure`].
+#[derive(Clone)]
+pub struct EguiOwnedClosure(Arc<EguiClosure>);
+
+impl EguiOwnedClosure {
+    /// Invokes the closure with `args`. Returns `None` if the closure did not
+    /// report a result, or if the result could not be decoded.
+    ///
+    /// Like [`EguiCallback::try_invoke`], a failed closure is reported by the call in progress
+    /// on this thread, and no closure is invoked after a failure until that call returns.
+    /// Failures outside of any call are logged instead.
+    pub fn call<A: Serialize + ?Sized, R: DeserializeOwned>(&self, args: &A) -> Option<R> {
+        if host_failed() {
+            return None;
+        }
+
+        let args = bincode::serialize(args).expect("Failed to encode closure arguments");
+        let mut ret = None::<Vec<u8>>;
+
+        let status = unsafe { (self.0.func)(EguiSliceU8::from_slice(&args), &mut ret as *mut _ as *mut c_void, self.0.data) };
+        if status == EguiCallbackStatus::Failed {
+            if !report_host_failure() {
+                log::error!(target: "egui_net", "Host closure {:#x} failed outside of a call", self.0.id);
+            }
+            return None;
+        }
+
+        match bincode::deserialize(&ret?) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                log::warn!(target: "egui_net", "Failed to decode result of {}: {error}", std::any::type_name::<R>());
+                None
+            }
+        }
+    }
+}
+
+impl Serialize for EguiOwnedClosure {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer {
+        self.0.id.serialize(serializer)
+    }
+}
+
+impl<'a> Deserialize<'a> for EguiOwnedClosure {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'a> {
+        let id = u64::deserialize(deserializer)?;
+
+        // Closures are not recorded in traces, so recorded ids are meaningless during replay.
+        if trace::replaying() {
+            return Ok(Self(Arc::new(EguiClosure {
+                func: replay_closure_fn,
+                data: std::ptr::null(),
+                release: replay_release_fn,
+                id: 0
+            })));
+        }
+
+        callback::lookup_closure(id)
+            .map(Self)
+            .ok_or_else(|| <D::Error as serde::de::Error>::custom(format!("Closure {id:#x} is not registered")))
+    }
+}
+
+/// The closure used in place of host closures during trace replay. Reports no result.
+unsafe extern "C" fn replay_closure_fn(_: EguiSliceU8, _: *mut c_void, _: *const c_void) -> EguiCallbackStatus {
+    EguiCallbackStatus::Completed
+}
+
+/// Releases a closure created during trace replay.
+unsafe extern "C" fn replay_release_fn(_: *const c_void) {}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Doubles its argument.
+    unsafe extern "C" fn double_test_closure(args: EguiSliceU8, ret: *mut c_void, _: *const c_void) -> EguiCallbackStatus {
+        let value = bincode::deserialize::<u32>(args.to_slice()).expect("Failed to decode argument");
+        let result = bincode::serialize(&(2 * value)).expect("Failed to encode result");
+        egui_closure_return(ret, EguiSliceU8::from_slice(&result));
+        EguiCallbackStatus::Completed
+    }
+
+    /// Fails every time that it is called.
+    unsafe extern "C" fn failing_test_closure(_: EguiSliceU8, _: *mut c_void, _: *const c_void) -> EguiCallbackStatus {
+        EguiCallbackStatus::Failed
+    }
+
+    /// Counts releases with the [`AtomicUsize`] passed as data.
+    unsafe extern "C" fn release_test_closure(data: *const c_void) {
+        (*(data as *const AtomicUsize)).fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Ensures that closures may be called from other threads and are released exactly once,
+    /// after they have been unregistered and the last reference has been dropped.
+    #[test]
+    fn releases_once() {
+        static RELEASED: AtomicUsize = AtomicUsize::new(0);
+
+        let id = unsafe { callback::egui_closure_register(double_test_closure, &RELEASED as *const _ as *const c_void, release_test_closure) };
+        let closure = bincode::deserialize::<EguiOwnedClosure>(&bincode::serialize(&id).expect("Failed to encode closure"))
+            .expect("Failed to decode closure");
+
+        let other = closure.clone();
+        let result = std::thread::spawn(move || other.call::<_, u32>(&21)).join().expect("Thread panicked");
+        assert_eq!(result, Some(42));
+
+        assert!(unsafe { callback::egui_closure_unregister(id) });
+        assert_eq!(RELEASED.load(Ordering::SeqCst), 0);
+        assert!(bincode::deserialize::<EguiOwnedClosure>(&bincode::serialize(&id).expect("Failed to encode closure")).is_err());
+
+        drop(closure);
+        assert_eq!(RELEASED.load(Ordering::SeqCst), 1);
+    }
+
+    /// Ensures that a closure passed to a call which fails before keeping it
+    /// is released as soon as the host unregisters it.
+    #[test]
+    fn releases_after_failed_call() {
+        static RELEASED: AtomicUsize = AtomicUsize::new(0);
+
+        let id = unsafe { callback::egui_closure_register(double_test_closure, &RELEASED as *const _ as *const c_void, release_test_closure) };
+        let args = bincode::serialize(&(0usize, id)).expect("Failed to encode args");
+        let result = unsafe { egui_invoke(EguiFn::egui_context_Context_set_request_repaint_callback, EguiSliceU8::from_slice(&args)) };
+        assert!(!result.success);
+        result.release();
+
+        assert!(unsafe { callback::egui_closure_unregister(id) });
+        assert_eq!(RELEASED.load(Ordering::SeqCst), 1);
+    }
+
+    /// Ensures that a failed closure is reported as a host error by the call that invoked it.
+    #[test]
+    fn reports_failed_closures() {
+        static RELEASED: AtomicUsize = AtomicUsize::new(0);
+
+        let ctx = EguiHandle::to_heap(Context::default());
+        let id = unsafe { callback::egui_closure_register(failing_test_closure, &RELEASED as *const _ as *const c_void, release_test_closure) };
+
+        let args = bincode::serialize(&(ctx.ptr, id)).expect("Failed to encode args");
+        let result = unsafe { egui_invoke(EguiFn::egui_context_Context_set_request_repaint_callback, EguiSliceU8::from_slice(&args)) };
+        assert!(result.success);
+        result.release();
+
+        // Requesting a repaint invokes the closure before the call returns.
+        let args = bincode::serialize(&ctx.ptr).expect("Failed to encode args");
+        let result = unsafe { egui_invoke(EguiFn::egui_context_Context_request_repaint, EguiSliceU8::from_slice(&args)) };
+        assert!(!result.success);
+        assert_eq!(result.error.kind, EguiErrorKind::HostError);
+        result.release();
+
+        // Outside of a call, the failure is only logged.
+        unsafe { (*(ctx.ptr as *const Context)).request_repaint_of(ViewportId::from_hash_of("other")) };
+        assert_eq!(HOST_FAILED.get(), None);
+
+        assert!(unsafe { callback::egui_closure_unregister(id) });
+        assert!(unsafe { egui_drop(ctx) });
+        assert_eq!(RELEASED.load(Ordering::SeqCst), 1);
+    }
+}
\ No newline at end of file
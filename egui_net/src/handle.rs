@@ -0,0 +1,267 @@
+//! Keeps a registry of live heap handles, so that invalid drops are caught
+//! and leaked objects can be reported to the host.
+
+use super::*;
+use std::any::TypeId;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The number of low bits in handle metadata which hold the [`EguiHandleKind`].
+const KIND_BITS: u32 = 8;
+
+/// The number of shards into which the live handles are divided. Must be a power of two.
+const SHARD_COUNT: usize = 32;
+
+/// All handles which have been allocated and not yet dropped, keyed by address and divided into
+/// shards by address. Resolving a pointer only takes a read lock on one shard, so that threads
+/// driving separate objects neither block nor contend with each other.
+static LIVE_HANDLES: LazyLock<[RwLock<HashMap<usize, HandleEntry>>; SHARD_COUNT]> = LazyLock::new(Default::default);
+
+/// The serial number of the most recently allocated handle.
+static LAST_SERIAL: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of drops that were rejected.
+static INVALID_DROPS: AtomicU64 = AtomicU64::new(0);
+
+/// Gets a report describing all outstanding handles. The returned data remains
+/// valid until the next report is requested on the same thread.
+#[no_mangle]
+pub unsafe extern "C" fn egui_handle_report() -> EguiHandleReport {
+    thread_local! {
+        /// The buffer to which outstanding handles will be written.
+        static REPORT_BUFFER: UnsafeCell<Vec<EguiHandleInfo>> = const { UnsafeCell::new(Vec::new()) };
+    }
+
+    let buffer = &mut *REPORT_BUFFER.with(UnsafeCell::get);
+
+    buffer.clear();
+    for shard in LIVE_HANDLES.iter() {
+        buffer.extend(shard.read().unwrap_or_else(PoisonError::into_inner).iter().map(|(ptr, entry)| EguiHandleInfo {
+            kind: entry.kind,
+            serial: (entry.metadata >> KIND_BITS) as u64,
+            ptr: *ptr
+        }));
+    }
+    buffer.sort_by_key(|x| x.serial);
+
+    let count = |kind| buffer.iter().filter(|x| x.kind == kind).count() as u64;
+    EguiHandleReport {
+        contexts: count(EguiHandleKind::Context),
+        painters: count(EguiHandleKind::Painter),
+        textures: count(EguiHandleKind::TextureHandle),
+        ffis: count(EguiHandleKind::EguiFfi),
+        others: count(EguiHandleKind::Other),
+        invalid_drops: INVALID_DROPS.load(Ordering::Relaxed),
+        handles: buffer.as_ptr(),
+        handle_count: buffer.len()
+    }
+}
+
+/// Records a newly-allocated object of type `T` at `ptr`, returning the metadata for its handle.
+pub(crate) fn register<T: 'static>(ptr: usize) -> usize {
+    let kind = EguiHandleKind::of::<T>();
+    let serial = LAST_SERIAL.fetch_add(1, Ordering::Relaxed) + 1;
+    let metadata = (serial << KIND_BITS) | kind as usize;
+
+    shard(ptr).write().unwrap_or_else(PoisonError::into_inner)
+        .insert(ptr, HandleEntry { kind, ty: TypeId::of::<T>(), metadata, drop: drop_box::<T> });
+    metadata
+}
+
+/// Checks that `ptr` refers to a live handle of type `T`, raising an error if it does not.
+#[track_caller]
+pub(crate) fn resolve<T: 'static>(ptr: usize) -> usize {
+    let live = shard(ptr).read().unwrap_or_else(PoisonError::into_inner)
+        .get(&ptr).is_some_and(|x| x.ty == TypeId::of::<T>());
+
+    if !live {
+        raise_error(EguiErrorKind::DanglingPointer, format!("Address {ptr:#x} is not a live {}", std::any::type_name::<T>()));
+    }
+
+    ptr
+}
+
+/// Unregisters and drops the object referenced by `handle`. If the handle is not live,
+/// then nothing is dropped and a description of the problem is returned instead.
+pub(crate) fn release(handle: EguiHandle) -> Result<(), String> {
+    let mut handles = shard(handle.ptr).write().unwrap_or_else(PoisonError::into_inner);
+
+    let error = match handles.get(&handle.ptr) {
+        Some(entry) if entry.metadata == handle.metadata => None,
+        Some(entry) => Some(format!("Handle {:#x} does not match the live {:?} at that address", handle.ptr, entry.kind)),
+        None if handle.metadata >> KIND_BITS <= LAST_SERIAL.load(Ordering::Relaxed) && EguiHandleKind::from_metadata(handle.metadata).is_some() =>
+            Some(format!("Handle {:#x} was already dropped", handle.ptr)),
+        None => Some(format!("Handle {:#x} was never allocated", handle.ptr))
+    };
+
+    if let Some(error) = error {
+        INVALID_DROPS.fetch_add(1, Ordering::Relaxed);
+        return Err(error);
+    }
+
+    let entry = handles.remove(&handle.ptr).expect("Handle was not live");
+    drop(handles);
+
+    unsafe {
+        (entry.drop)(handle.ptr);
+    }
+
+    Ok(())
+}
+
+/// Unregisters and drops every live handle, returning how many were dropped. This
+/// invalidates handles held by all threads, so it should only be used by harnesses
+/// that own every handle in the process.
+#[cfg(fuzzing)]
+pub fn release_all() -> usize {
+    let mut count = 0;
+    for shard in LIVE_HANDLES.iter() {
+        let handles = std::mem::take(&mut *shard.write().unwrap_or_else(PoisonError::into_inner));
+        count += handles.len();
+
+        for (ptr, entry) in handles {
+            unsafe {
+                (entry.drop)(ptr);
+            }
+        }
+    }
+
+    count
+}
+
+/// Gets the shard of the live handles which holds the handle at `ptr`.
+fn shard(ptr: usize) -> &'static RwLock<HashMap<usize, HandleEntry>> {
+    // Boxed objects are aligned, so the lowest bits carry little information.
+    &LIVE_HANDLES[(ptr >> 4) & (SHARD_COUNT - 1)]
+}
+
+/// Frees a boxed `T` at the given address.
+///
+/// # Safety
+///
+/// `ptr` must have been created with [`Box::into_raw`], and may not be used afterward.
+unsafe fn drop_box<T>(ptr: usize) {
+    drop(Box::from_raw(ptr as *mut T));
+}
+
+/// Identifies the type of object referenced by an [`EguiHandle`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub enum EguiHandleKind {
+    /// The handle refers to a [`Context`].
+    Context,
+    /// The handle refers to a [`Painter`].
+    Painter,
+    /// The handle refers to a [`TextureHandle`].
+    TextureHandle,
+    /// The handle refers to an [`EguiFfi`].
+    EguiFfi,
+    /// The handle refers to some other type.
+    Other
+}
+
+impl EguiHandleKind {
+    /// All kinds, in discriminant order.
+    const ALL: [Self; 5] = [Self::Context, Self::Painter, Self::TextureHandle, Self::EguiFfi, Self::Other];
+
+    /// Gets the kind for objects of type `T`.
+    fn of<T: 'static>() -> Self {
+        let ty = TypeId::of::<T>();
+        if ty == TypeId::of::<Context>() {
+            Self::Context
+        }
+        else if ty == TypeId::of::<Painter>() {
+            Self::Painter
+        }
+        else if ty == TypeId::of::<TextureHandle>() {
+            Self::TextureHandle
+        }
+        else if ty == TypeId::of::<EguiFfi>() {
+            Self::EguiFfi
+        }
+        else {
+            Self::Other
+        }
+    }
+
+    /// Gets the kind encoded in handle metadata, if it is valid.
+    fn from_metadata(metadata: usize) -> Option<Self> {
+        Self::ALL.get(metadata & ((1 << KIND_BITS) - 1)).copied()
+    }
+}
+
+/// Describes the handles that are currently live.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct EguiHandleReport {
+    /// The number of live [`Context`] handles.
+    pub contexts: u64,
+    /// The number of live [`Painter`] handles.
+    pub painters: u64,
+    /// The number of live [`TextureHandle`] handles.
+    pub textures: u64,
+    /// The number of live [`EguiFfi`] handles.
+    pub ffis: u64,
+    /// The number of live handles of other types.
+    pub others: u64,
+    /// The number of drops that were rejected because their handles were not live.
+    pub invalid_drops: u64,
+    /// A pointer to the first outstanding handle, ordered by allocation.
+    pub handles: *const EguiHandleInfo,
+    /// The number of outstanding handles.
+    pub handle_count: usize
+}
+
+/// Describes a single outstanding handle.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct EguiHandleInfo {
+    /// The type of object that the handle refers to.
+    pub kind: EguiHandleKind,
+    /// A number which increases with each allocated handle.
+    pub serial: u64,
+    /// The address of the object.
+    pub ptr: usize
+}
+
+/// Describes a live handle.
+struct HandleEntry {
+    /// The type of object that the handle refers to.
+    kind: EguiHandleKind,
+    /// The exact type of the object.
+    ty: TypeId,
+    /// The metadata given to the host for this handle.
+    metadata: usize,
+    /// Frees the object.
+    drop: unsafe fn(usize)
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensures that double drops and forged handles are rejected without freeing anything.
+    #[test]
+    fn rejects_invalid_drops() {
+        let handle = EguiHandle::to_heap(EguiFfi::default());
+        let forged = EguiHandle { ptr: handle.ptr, metadata: handle.metadata ^ (1 << KIND_BITS) };
+
+        assert!(release(forged).is_err());
+        assert!(release(handle).is_ok());
+        assert!(release(handle).is_err());
+        assert!(unsafe { egui_handle_report() }.invalid_drops >= 2);
+    }
+
+    /// Ensures that pointers are only resolved for live handles of the requested type.
+    #[test]
+    fn resolves_live_handles() {
+        let handle = EguiHandle::to_heap(EguiFfi::default());
+        assert_eq!(resolve::<EguiFfi>(handle.ptr), handle.ptr);
+
+        let error = catch_unwind(|| resolve::<Context>(handle.ptr)).expect_err("Handle of another type was resolved");
+        assert_eq!(error.downcast_ref::<EguiPanic>().map(|x| x.kind), Some(EguiErrorKind::DanglingPointer));
+
+        assert!(release(handle).is_ok());
+        assert!(catch_unwind(|| resolve::<EguiFfi>(handle.ptr)).is_err());
+    }
+}
@@ -1,10 +1,5 @@
 #![allow(warnings)]
 
-#![feature(fn_traits)]
-#![feature(thread_local)]
-#![feature(tuple_trait)]
-#![feature(unboxed_closures)]
-
 use egui::*;
 use egui::color_picker::*;
 use egui::ecolor::*;
@@ -40,18 +35,45 @@ use egui::util::undoer::*;
 use egui_net_ffi::*;
 use serde::*;
 use serde::de::*;
+use std::any::Any;
+use std::backtrace::Backtrace;
 use std::borrow::*;
+use std::cell::Cell;
+use std::cell::UnsafeCell;
 use std::collections::*;
 use std::ffi::*;
 use std::marker::PhantomData;
-use std::marker::Tuple;
 use std::mem::*;
 use std::ops::*;
 use std::sync::atomic::AtomicUsize;
 use std::time::*;
 use std::panic;
 use std::panic::catch_unwind;
+use std::panic::AssertUnwindSafe;
 use std::sync::*;
+use claim::EguiClaim;
+use closure::EguiOwnedClosure;
+use patch::EguiPatch;
+use scope::EguiScope;
+
+pub mod callback;
+pub mod claim;
+pub mod closure;
+pub(crate) mod handle;
+pub mod patch;
+pub mod profile;
+pub mod result;
+pub(crate) mod scope;
+pub mod trace;
+pub mod viewports;
+
+/// Internals which the fuzz targets use to stand in for a host. These bypass the checks
+/// that the bindings perform, so they are only exported when building with `cfg(fuzzing)`.
+#[cfg(fuzzing)]
+pub mod fuzzing {
+    pub use crate::handle::release_all;
+    pub use crate::scope::EguiScope;
+}
 
 /// The global memory allocator to use.
 #[global_allocator]
@@ -60,60 +82,53 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 include!(concat!(env!("CARGO_MANIFEST_DIR"), "/../target/bindings/egui_fn.rs"));
 
 /// A registry containing all `egui` functions callable from C#.
-const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS
-    // Bindings for Area
-    .with(EguiFn::egui_containers_area_Area_show, |ctx: EguiPointer<Context>, area: Area, callback: EguiCallback| unsafe { area.show(ctx.get(), |ui| callback.invoke(ui as *const _ as *const _)).response })
+const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS.overlay(MANUAL_EGUI_FNS);
+
+/// The functions which are bound by hand, either because they could not be autobound
+/// or because the autogenerated binding is incorrect.
+const MANUAL_EGUI_FNS: EguiFnMap = egui_fn_map()
     // Bindings for AreaState
     .with(EguiFn::egui_containers_area_AreaState_load, |ctx: EguiPointer<Context>, id: Id| unsafe { AreaState::load(ctx.get(), id) })
     // Bindings fot Atoms
     .with(EguiFn::egui_atomics_atoms_Atoms_text, |atoms: Atoms| atoms.text().map(|x| x.into_owned()))
     // Bindings for AtomLayoutResponse
     .with(EguiFn::egui_atomics_atom_layout_AtomLayoutResponse_custom_rects, |r: AtomLayoutResponse| r.custom_rects().collect::<Vec<_>>())
-    // Bindings for CentralPanel
-    .with(EguiFn::egui_containers_panel_CentralPanel_show, |ctx: EguiPointer<Context>, panel: CentralPanel, callback: EguiCallback| unsafe {
-        panel.show(ctx.get(), |ui| {
-            callback.invoke(ui as *const _ as *const _);
-        }).response
-    })
-    .with(EguiFn::egui_containers_panel_CentralPanel_show_inside, |mut ui: EguiPointer<Ui>, panel: CentralPanel, callback: EguiCallback| unsafe {
-        panel.show_inside(ui.get_mut(), |ui| {
-            callback.invoke(ui as *const _ as *const _);
-        }).response
-    })
     // Bindings for Checkbox
     .with(EguiFn::egui_widgets_checkbox_Checkbox_ui, |mut ui: EguiPointer<Ui>, atoms: Atoms, mut checked: bool, indeterminate: bool| unsafe { (ui.get_mut().add(Checkbox::new(&mut checked, atoms).indeterminate(indeterminate)), checked) })
     // Bindings for CollapsingHeader
     .with(EguiFn::egui_containers_collapsing_header_CollapsingHeader_show, |mut ui: EguiPointer<Ui>, obj: CollapsingHeader, callback: EguiCallback| unsafe {
-        let result = obj.show(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _));
+        let result = obj.show(ui.get_mut(), |ui| callback.invoke_scoped(ui));
         (result.header_response, result.body_response, result.openness)
     })
     .with(EguiFn::egui_containers_collapsing_header_CollapsingHeader_show_unindented, |mut ui: EguiPointer<Ui>, obj: CollapsingHeader, callback: EguiCallback| unsafe {
-        let result = obj.show_unindented(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _));
+        let result = obj.show_unindented(ui.get_mut(), |ui| callback.invoke_scoped(ui));
         (result.header_response, result.body_response, result.openness)
     })
     // Bindings for CollapsingState
    .with(EguiFn::egui_containers_collapsing_header_CollapsingState_show_body_indented, |mut state: CollapsingState, header_response: Response, mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe {
-        (state.show_body_indented(&header_response, ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _)).map(|x| x.response), state)
+        (state.show_body_indented(&header_response, ui.get_mut(), |ui| callback.invoke_scoped(ui)).map(|x| x.response), state)
     })    
    .with(EguiFn::egui_containers_collapsing_header_CollapsingState_show_body_unindented, |mut state: CollapsingState, mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe {
-        (state.show_body_unindented(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _)).map(|x| x.response), state)
+        (state.show_body_unindented(ui.get_mut(), |ui| callback.invoke_scoped(ui)).map(|x| x.response), state)
     })    
    .with(EguiFn::egui_containers_collapsing_header_CollapsingState_show_header, |mut state: CollapsingState, mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe {
-        let result = state.show_header(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _));
+        let result = state.show_header(ui.get_mut(), |ui| callback.invoke_scoped(ui));
         (result.toggle_button_response, result.header_response.response, result.state)
     })
-    .with(EguiFn::egui_containers_collapsing_header_EguiCollapsingStateShowToggleButtonParams_unpack, |mut x: EguiPointer<EguiCollapsingStateShowToggleButtonParams>| unsafe {
+    .with(EguiFn::egui_containers_collapsing_header_EguiCollapsingStateShowToggleButtonParams_unpack, |mut x: EguiPointer<EguiCollapsingStateShowToggleButtonParams<'static>>| unsafe {
         let params = x.get_mut();
-        (params.ui as *const _ as usize, params.openness, params.response.clone())
+        (scope::lookup::<Ui>(params.ui), params.openness, params.response.clone())
     })
    .with(EguiFn::egui_containers_collapsing_header_CollapsingState_show_toggle_button, |mut state: CollapsingState, mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe {
         state.show_toggle_button(ui.get_mut(), move |ui, openness, response| {
+            let ctx = ui.ctx().clone();
             let pack = EguiCollapsingStateShowToggleButtonParams {
                 ui,
                 openness,
                 response
             };
-            callback.invoke(&pack as *const _ as *const _);
+            let scope = EguiScope::enter(&pack as *const _ as *const EguiCollapsingStateShowToggleButtonParams<'static>);
+            callback.invoke(Some(&ctx), scope.ptr() as *const _);
         })
     })
     // Bindings for Color32
@@ -141,42 +156,71 @@ const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS
     .with(EguiFn::egui_context_Context_graphics_mut, |ctx: EguiPointer<Context>, state: GraphicLayers| unsafe { ctx.get().graphics_mut(|i| *i = state) })
     .with(EguiFn::egui_context_Context_input_for, |ctx: EguiPointer<Context>, id: ViewportId| unsafe { ctx.get().input_for(id, |i| i.clone()) })
     .with(EguiFn::egui_context_Context_input_mut_for, |ctx: EguiPointer<Context>, id: ViewportId, state: InputState| unsafe { ctx.get().input_mut_for(id, |i| *i = state) })
+    .with(EguiFn::egui_context_Context_input_patch, |ctx: EguiPointer<Context>, field: String, value: Vec<u8>| unsafe { ctx.get().input_mut(|i| i.patch(&field, &value)) })
+    .with(EguiFn::egui_context_Context_input_patch_for, |ctx: EguiPointer<Context>, id: ViewportId, field: String, value: Vec<u8>| unsafe { ctx.get().input_mut_for(id, |i| i.patch(&field, &value)) })
+    .with(EguiFn::egui_context_Context_options_patch, |ctx: EguiPointer<Context>, field: String, value: Vec<u8>| unsafe { ctx.get().options_mut(|i| i.patch(&field, &value)) })
+    .with(EguiFn::egui_context_Context_tessellation_options_patch, |ctx: EguiPointer<Context>, field: String, value: Vec<u8>| unsafe { ctx.get().tessellation_options_mut(|i| i.patch(&field, &value)) })
+    .with(EguiFn::egui_context_Context_graphics_set_layer, |ctx: EguiPointer<Context>, layer_id: LayerId, shapes: Vec<ClippedShape>| unsafe {
+        ctx.get().graphics_mut(|i| {
+            let list = i.entry(layer_id);
+            *list = PaintList::default();
+            for shape in shapes {
+                list.add(shape.clip_rect, shape.shape);
+            }
+        })
+    })
     .with(EguiFn::egui_context_Context_output, |ctx: EguiPointer<Context>| unsafe { ctx.get().output(|i| i.clone()) })
     .with(EguiFn::egui_context_Context_output_mut, |ctx: EguiPointer<Context>, state: PlatformOutput| unsafe { ctx.get().output_mut(|i| *i = state) })
     .with(EguiFn::egui_context_Context_load_texture, |ctx: EguiPointer<Context>, name: String, image: ImageData, options: TextureOptions| unsafe { EguiHandle::to_heap(ctx.get().load_texture(name, image, options)) })
-    .with(EguiFn::egui_context_Context_memory, |ctx: EguiPointer<Context>, callback: EguiCallback| unsafe { ctx.get().memory(|i| {
-        let mut cloned = i.clone();
-        callback.invoke(&mut cloned as *const _ as *const _);
-    }) })
-    .with(EguiFn::egui_context_Context_memory_mut, |ctx: EguiPointer<Context>, callback: EguiCallback| unsafe { ctx.get().memory_mut(|i| callback.invoke(i as *const _ as *const _)) })
+    // The host may write through the pointer it receives, so even reads need exclusive access to the live object
+    .with(EguiFn::egui_context_Context_memory, |ctx: EguiPointer<Context>, callback: EguiCallback| unsafe { ctx.get().memory_mut(|i| callback.invoke_scoped(i)) })
+    .with(EguiFn::egui_context_Context_memory_mut, |ctx: EguiPointer<Context>, callback: EguiCallback| unsafe { ctx.get().memory_mut(|i| callback.invoke_scoped(i)) })
+    .with(EguiFn::egui_context_Context_begin_pass, |ctx: EguiPointer<Context>, new_input: RawInput| unsafe {
+        claim::begin_context(ctx.get(), || ctx.get().begin_pass(new_input));
+    })
+    .with(EguiFn::egui_context_Context_end_pass, |ctx: EguiPointer<Context>| unsafe {
+        claim::end_context(ctx.get(), || ctx.get().end_pass())
+    })
     .with(EguiFn::egui_context_Context_run, |ctx: EguiPointer<Context>, raw_input: RawInput, callback: EguiCallback| unsafe {
+        let _claim = EguiClaim::context(ctx.get());
         ctx.get().run(raw_input, |ctx| {
-            callback.invoke(std::ptr::null());
+            callback.invoke(Some(ctx), std::ptr::null());
         })
     })
     .with(EguiFn::egui_context_Context_run_ffi, |ctx: EguiPointer<Context>, mut input: EguiPointer<EguiFfi>, callback: EguiCallback| unsafe {
-        let raw_input = input.get().raw_input();
-        
-        let mut full_output = ctx.get().run(raw_input, |ctx| {
-            callback.invoke(std::ptr::null());
+        let _claim = EguiClaim::context(ctx.get());
+        let _input_claim = EguiClaim::acquire(input.get() as *const EguiFfi as usize, "EguiFfi");
+        trace::sync_ffi_input(input.get_mut());
+        viewports::run_ffi(ctx.get(), input, |ctx| {
+            callback.invoke(Some(ctx), std::ptr::null());
         });
-
-        let primitives = ctx.get().tessellate(take(&mut full_output.shapes), full_output.pixels_per_point);
-        let meshes = primitives.into_iter().filter_map(|x| if let Primitive::Mesh(mesh) = x.primitive {
-            Some((x.clip_rect, mesh))
-        }
-        else {
-            None
-        });
-        input.get_mut().set_full_output(full_output);
-        input.get_mut().set_meshes(meshes);
+    })
+    .with(EguiFn::egui_context_Context_show_viewport_deferred, |ctx: EguiPointer<Context>, id: ViewportId, builder: ViewportBuilder, callback: EguiOwnedClosure| unsafe {
+        ctx.get().show_viewport_deferred(id, builder, move |_, class| {
+            callback.call::<_, ()>(&class);
+        })
+    })
+    .with(EguiFn::egui_context_Context_show_viewport_immediate, |ctx: EguiPointer<Context>, id: ViewportId, builder: ViewportBuilder, callback: EguiCallback| unsafe {
+        viewports::show_immediate(ctx.get(), id, builder, |ctx, class| {
+            let class = class as u32;
+            callback.invoke(Some(ctx), &class as *const u32 as *const c_void);
+        })
+    })
+    .with(EguiFn::egui_context_Context_viewport, |ctx: EguiPointer<Context>| unsafe {
+        ctx.get().viewport(|i| (i.class, i.builder.clone(), i.input.clone(), i.used))
+    })
+    .with(EguiFn::egui_context_Context_viewport_for, |ctx: EguiPointer<Context>, id: ViewportId| unsafe {
+        ctx.get().viewport_for(id, |i| (i.class, i.builder.clone(), i.input.clone(), i.used))
     })
     .with(EguiFn::egui_context_Context_fonts, |ctx: EguiPointer<Context>, callback: EguiCallback| unsafe {
-        ctx.get().fonts(|fonts| callback.invoke(fonts as *const _ as *const _))
+        ctx.get().fonts(|fonts| callback.invoke_scoped(fonts))
+    })
+    .with(EguiFn::egui_context_Context_set_request_repaint_callback, |ctx: EguiPointer<Context>, callback: EguiOwnedClosure| unsafe {
+        ctx.get().set_request_repaint_callback(move |info| {
+            callback.call::<_, ()>(&(info.viewport_id, info.delay.as_secs_f64(), info.current_cumulative_pass_nr));
+        })
     })
     .with(EguiFn::egui_context_Context_register_widget_info, |ctx: EguiPointer<Context>, id: Id, info: WidgetInfo| unsafe { ctx.get().register_widget_info(id, || info.clone()) })
-    // Bindings for ComboBox
-    .with(EguiFn::egui_containers_combo_box_ComboBox_show_ui, |mut ui: EguiPointer<Ui>, obj: ComboBox, callback: EguiCallback| unsafe { obj.show_ui(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _)).response })
     // Bindings for DragValue
     .with(EguiFn::egui_widgets_drag_value_DragValue_ui, |mut ui: EguiPointer<Ui>, obj: SerializableDragValue, mut value: f64| unsafe {
         (ui.get_mut().add(obj.to_drag_value(&mut value)), value)
@@ -186,23 +230,37 @@ const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS
     .with(EguiFn::ecolor_hsva_rgb_from_hsv, |hsv: (f32, f32, f32)| rgb_from_hsv(hsv))
     // Bindings for EguiFfi
     .with(EguiFn::egui_EguiFfi_new, || EguiHandle::to_heap(EguiFfi::default()))
+    .with(EguiFn::egui_EguiFfi_set_mesh_layout, |mut ffi: EguiPointer<EguiFfi>, mesh_layout: FfiMeshLayout| unsafe {
+        let _claim = EguiClaim::acquire(ffi.get() as *const EguiFfi as usize, "EguiFfi");
+        ffi.get_mut().set_mesh_layout(mesh_layout);
+    })
+    .with(EguiFn::egui_EguiFfi_set_output_format, |mut ffi: EguiPointer<EguiFfi>, physical_pixels: bool, linear_colors: bool, u16_indices: bool, integer_scissor_rects: bool| unsafe {
+        let _claim = EguiClaim::acquire(ffi.get() as *const EguiFfi as usize, "EguiFfi");
+        ffi.get_mut().set_output_format(FfiOutputFormat { physical_pixels, linear_colors, u16_indices, integer_scissor_rects });
+    })
+    .with(EguiFn::egui_EguiFfi_set_paint_mode, |mut ffi: EguiPointer<EguiFfi>, paint_mode: FfiPaintMode| unsafe {
+        let _claim = EguiClaim::acquire(ffi.get() as *const EguiFfi as usize, "EguiFfi");
+        ffi.get_mut().set_paint_mode(paint_mode);
+    })
     // Bindings for epaint
     .with(EguiFn::epaint_shape_transform_adjust_colors, |mut shape: Shape, callback: EguiCallback| unsafe {
-        shape_transform::adjust_colors(&mut shape, move |color| callback.invoke(color as *const _ as *const _));
+        shape_transform::adjust_colors(&mut shape, move |color| callback.invoke(None, color as *const _ as *const _));
         shape
     })
     .with(EguiFn::epaint_text_fonts_FontDefinitions_builtin_font_names, || FontDefinitions::builtin_font_names().into_iter().map(ToString::to_string).collect::<Vec<_>>())
-    // Bindings for Frame
-    .with(EguiFn::egui_containers_frame_Frame_show, |mut ui: EguiPointer<Ui>, frame: Frame, callback: EguiCallback| unsafe { frame.show(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _)).response })
     // Bindings for FrameDurations
     .with(EguiFn::egui_widgets_image_FrameDurations_all, |x: FrameDurations| x.all().copied().collect::<Vec<_>>())
     // Bindings for GraphicLayers
     .with(EguiFn::egui_layers_GraphicLayers_drain, |mut x: GraphicLayers, area_order: Vec<LayerId>, to_global: egui::ahash::HashMap<LayerId, TSTransform>| (x.drain(&area_order, &to_global), x))
     .with(EguiFn::egui_layers_GraphicLayers_get, |mut x: GraphicLayers, layer_id: LayerId| x.get(layer_id).cloned())
     // Bindings for Grid
-    .with(EguiFn::egui_grid_Grid_show, |mut ui: EguiPointer<Ui>, grid: Grid, callback: EguiCallback| unsafe {
+    .with(EguiFn::egui_grid_Grid_show, |mut ui: EguiPointer<Ui>, mut grid: Grid, row_color: Option<EguiOwnedClosure>, callback: EguiCallback| unsafe {
+        if let Some(row_color) = row_color {
+            grid = grid.with_row_color(move |row, style| row_color.call::<_, Option<Color32>>(&(row, style)).flatten());
+        }
+
         grid.show(ui.get_mut(), |ui| {
-            callback.invoke(ui as *const _ as *const _);
+            callback.invoke_scoped(ui);
         }).response
     })
     // Bindings for HexColor
@@ -229,25 +287,26 @@ const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS
     // Bindings for Memory
     .with(EguiFn::egui_memory_Memory_options, |m: EguiPointer<Memory>| unsafe { m.get().options.clone() })
     .with(EguiFn::egui_memory_Memory_set_options, |mut m: EguiPointer<Memory>, opts: Options| unsafe { m.get_mut().options = opts; })
+    .with(EguiFn::egui_memory_Memory_options_patch, |mut m: EguiPointer<Memory>, field: String, value: Vec<u8>| unsafe { m.get_mut().options.patch(&field, &value) })
     .with(EguiFn::egui_memory_Memory_layer_ids, |m: EguiPointer<Memory>| unsafe { m.get().layer_ids().collect::<Vec<_>>() })
     // Bindings for MenuBar
     .with(EguiFn::egui_containers_menu_MenuBar_ui, |mut ui: EguiPointer<Ui>, obj: MenuBar, callback: EguiCallback| unsafe {
-        obj.ui(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _)).response
+        obj.ui(ui.get_mut(), |ui| callback.invoke_scoped(ui)).response
     })
     // Bindings for MenuButton
     .with(EguiFn::egui_containers_menu_MenuButton_ui, |mut ui: EguiPointer<Ui>, obj: MenuButton, callback: EguiCallback| unsafe {
-        let (response, maybe_response) = obj.ui(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _));
+        let (response, maybe_response) = obj.ui(ui.get_mut(), |ui| callback.invoke_scoped(ui));
         (response, maybe_response.map(|x| x.response))
     })
     // Bindings for SubMenuButton
     .with(EguiFn::egui_containers_menu_SubMenuButton_ui, |mut ui: EguiPointer<Ui>, obj: SubMenuButton, callback: EguiCallback| unsafe {
-        let (response, maybe_response) = obj.ui(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _));
+        let (response, maybe_response) = obj.ui(ui.get_mut(), |ui| callback.invoke_scoped(ui));
         (response, maybe_response.map(|x| x.response))
     })
     // Bindings for Modal
     .with(EguiFn::egui_containers_modal_Modal_show, |ctx: EguiPointer<Context>, obj: Modal, callback: EguiCallback| unsafe {
         let result = obj.show(ctx.get(), |ui| {
-            callback.invoke(ui as *const _ as *const _);
+            callback.invoke_scoped(ui);
         });
 
         (result.response, result.backdrop_response, result.is_top_modal, result.any_popup_open)
@@ -275,6 +334,9 @@ const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS
     .with(EguiFn::egui_painter_Painter_with_layer_id, |painter: EguiPointer<Painter>, layer_id: LayerId| unsafe { EguiHandle::to_heap(painter.get().clone().with_layer_id(layer_id)) })
     .with(EguiFn::egui_painter_Painter_with_clip_rect, |painter: EguiPointer<Painter>, clip_rect: Rect| unsafe { EguiHandle::to_heap(painter.get().clone().with_clip_rect(clip_rect)) })
     .with(EguiFn::egui_painter_Painter_add, |painter: EguiPointer<Painter>, shape: Shape| unsafe { painter.get().add(shape) })
+    .with(EguiFn::egui_painter_Painter_add_ffi_callback, |painter: EguiPointer<Painter>, rect: Rect, id: u64| unsafe {
+        painter.get().add(PaintCallback { rect, callback: Arc::new(FfiPaintCallback { id }) })
+    })
     .with(EguiFn::egui_painter_Painter_for_each_shape, |painter: EguiPointer<Painter>| unsafe {
         let mut shapes = Vec::new();
         painter.get().for_each_shape(|x| shapes.push(x.clone()));
@@ -289,7 +351,7 @@ const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS
     .with(EguiFn::egui_containers_popup_Popup_is_id_open, |ctx: EguiPointer<Context>, id: Id| unsafe { Popup::is_id_open(ctx.get(), id) })
     .with(EguiFn::egui_containers_popup_Popup_position_of_id, |ctx: EguiPointer<Context>, id: Id| unsafe { Popup::position_of_id(ctx.get(), id) })
     .with(EguiFn::egui_containers_popup_Popup_show, |ctx: EguiPointer<Context>, popup: SerializablePopup, mut is_open: bool, callback: EguiCallback| unsafe {
-        let response = popup.to_popup(ctx.get(), &mut is_open).show(|ui| callback.invoke(ui as *const _ as *const _));
+        let response = popup.to_popup(ctx.get(), &mut is_open).show(|ui| callback.invoke_scoped(ui));
         (response.map(|x| x.response), is_open)
     })
     // Bindings for RawInput
@@ -306,32 +368,29 @@ const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS
     .with(EguiFn::emath_rect_Rect_center, |r: Rect| r.center())
     .with(EguiFn::emath_rect_Rect_set_center, |mut r: Rect, p: Pos2| { r.set_center(p); r })
     .with(EguiFn::emath_rect_Rect_extend_with, |mut r: Rect, p: Pos2| { r.extend_with(p); r })
-    .with(EguiFn::emath_rect_Rect_extend_with, |mut r: Rect, p: Pos2| { r.extend_with(p); r })
     .with(EguiFn::emath_rect_Rect_extend_with_x, |mut r: Rect, p: f32| { r.extend_with_x(p); r })
     .with(EguiFn::emath_rect_Rect_extend_with_y, |mut r: Rect, p: f32| { r.extend_with_y(p); r })
     // Bindings for RectAlign
     .with(EguiFn::emath_rect_align_RectAlign_find_best_align, |values_to_try: Vec<RectAlign>, screen_rect: Rect, parent_rect: Rect, gap: f32, expected_size: Vec2| RectAlign::find_best_align(values_to_try.into_iter(), screen_rect, parent_rect, gap, expected_size))
-    // Bindings for Resize
-    .with(EguiFn::egui_containers_resize_Resize_show, |mut ui: EguiPointer<Ui>, obj: Resize, callback: EguiCallback| unsafe { obj.show(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _)) })
     // Bindings for Response
     .with(EguiFn::egui_response_Response_mark_changed, |mut x: Response| { x.mark_changed(); x })
-    .with(EguiFn::egui_response_Response_mark_changed, |mut x: Response| { x.set_close(); x })
+    .with(EguiFn::egui_response_Response_set_close, |mut x: Response| { x.set_close(); x })
     .with(EguiFn::egui_response_Response_widget_info, |x: Response, info: WidgetInfo| x.widget_info(|| info.clone()))
     // Bindings for Scene
-    .with(EguiFn::egui_containers_scene_Scene_show, |mut ui: EguiPointer<Ui>, scene: Scene, mut scene_rect: Rect, callback: EguiCallback| unsafe { (scene.show(ui.get_mut(), &mut scene_rect, |ui| callback.invoke(ui as *const _ as *const _)).response, scene_rect) })
+    .with(EguiFn::egui_containers_scene_Scene_show, |mut ui: EguiPointer<Ui>, scene: Scene, mut scene_rect: Rect, callback: EguiCallback| unsafe { (scene.show(ui.get_mut(), &mut scene_rect, |ui| callback.invoke_scoped(ui)).response, scene_rect) })
     // Bindings for ScrollArea
     .with(EguiFn::egui_containers_scroll_area_ScrollArea_show, |mut ui: EguiPointer<Ui>, obj: ScrollArea, callback: EguiCallback| unsafe {
-        let result = obj.show(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _));
+        let result = obj.show(ui.get_mut(), |ui| callback.invoke_scoped(ui));
         (result.id, result.state, result.content_size, result.inner_rect)
     })
     .with(EguiFn::egui_containers_scroll_area_ScrollArea_show_rows, |mut ui: EguiPointer<Ui>, area: ScrollArea, row_height_sans_spacing: f32, total_rows: usize, callback: EguiCallback| unsafe {
         let result = area.show_rows(ui.get_mut(), row_height_sans_spacing, total_rows,
-            |ui, rows| callback.invoke(&EguiScrollAreaShowRowsParams { ui: ui as *const _ as usize, start: rows.start, end: rows.end } as *const _ as *const _));
+            |ui, rows| { let ctx = ui.ctx().clone(); let ui = EguiScope::enter(ui); callback.invoke(Some(&ctx), &EguiScrollAreaShowRowsParams { ui: ui.ptr(), start: rows.start, end: rows.end } as *const _ as *const _) });
         (result.id, result.state, result.content_size, result.inner_rect)
     })
     .with(EguiFn::egui_containers_scroll_area_ScrollArea_show_viewport, |mut ui: EguiPointer<Ui>, area: ScrollArea, callback: EguiCallback| unsafe {
         let result = area.show_viewport(ui.get_mut(),
-            |ui, rect| callback.invoke(&EguiScrollAreaShowViewportParams { ui: ui as *const _ as usize, min_x: rect.min.x, min_y: rect.min.y, max_x: rect.max.x, max_y: rect.max.y } as *const _ as *const _));
+            |ui, rect| { let ctx = ui.ctx().clone(); let ui = EguiScope::enter(ui); callback.invoke(Some(&ctx), &EguiScrollAreaShowViewportParams { ui: ui.ptr(), min_x: rect.min.x, min_y: rect.min.y, max_x: rect.max.x, max_y: rect.max.y } as *const _ as *const _) });
         (result.id, result.state, result.content_size, result.inner_rect)
     })
     // Bindings for Sense
@@ -340,43 +399,38 @@ const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS
     .with(EguiFn::egui_sense_Sense_senses_drag, |x: u8| Sense::from_bits_truncate(x).senses_drag())
     .with(EguiFn::egui_sense_Sense_is_focusable, |x: u8| Sense::from_bits_truncate(x).is_focusable())
     // Bindings for SidePanel
-    .with(EguiFn::egui_containers_panel_SidePanel_show, |ctx: EguiPointer<Context>, panel: SidePanel, callback: EguiCallback| unsafe { panel.show(ctx.get(), |ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_containers_panel_SidePanel_show_inside, |mut ui: EguiPointer<Ui>, panel: SidePanel, callback: EguiCallback| unsafe { panel.show_inside(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_containers_panel_SidePanel_show_animated_inside, |mut ui: EguiPointer<Ui>, is_expanded: bool, panel: SidePanel, callback: EguiCallback| unsafe { panel.show_animated_inside(ui.get_mut(), is_expanded, |ui| callback.invoke(ui as *const _ as *const _)).map(|x| x.response) })
-    .with(EguiFn::egui_containers_panel_SidePanel_show_animated, |ctx: EguiPointer<Context>, is_expanded: bool, panel: SidePanel, callback: EguiCallback| unsafe { panel.show_animated(ctx.get(), is_expanded, |ui| callback.invoke(ui as *const _ as *const _)).map(|x| x.response) })
-    .with(EguiFn::egui_containers_panel_SidePanel_show_animated_between, |ctx: EguiPointer<Context>, is_expanded: bool, collapsed_panel: SidePanel, expanded_panel: SidePanel, callback: EguiCallback| unsafe { SidePanel::show_animated_between(ctx.get(), is_expanded, collapsed_panel, expanded_panel, |ui, t| callback.invoke(&EguiAnimatedUi::new(ui, t) as *const _ as *const _)).map(|x| x.response) })
-    .with(EguiFn::egui_containers_panel_SidePanel_show_animated_between_inside, |mut ui: EguiPointer<Ui>, is_expanded: bool, collapsed_panel: SidePanel, expanded_panel: SidePanel, callback: EguiCallback| unsafe { SidePanel::show_animated_between_inside(ui.get_mut(), is_expanded, collapsed_panel, expanded_panel, |ui, t| callback.invoke(&EguiAnimatedUi::new(ui, t) as *const _ as *const _)).response })
+    .with(EguiFn::egui_containers_panel_SidePanel_show_animated_between, |ctx: EguiPointer<Context>, is_expanded: bool, collapsed_panel: SidePanel, expanded_panel: SidePanel, callback: EguiCallback| unsafe { SidePanel::show_animated_between(ctx.get(), is_expanded, collapsed_panel, expanded_panel, |ui, t| { let ctx = ui.ctx().clone(); let ui = EguiScope::enter(ui); callback.invoke(Some(&ctx), &EguiAnimatedUi::new(&ui, t) as *const _ as *const _) }).map(|x| x.response) })
+    .with(EguiFn::egui_containers_panel_SidePanel_show_animated_between_inside, |mut ui: EguiPointer<Ui>, is_expanded: bool, collapsed_panel: SidePanel, expanded_panel: SidePanel, callback: EguiCallback| unsafe { SidePanel::show_animated_between_inside(ui.get_mut(), is_expanded, collapsed_panel, expanded_panel, |ui, t| { let ctx = ui.ctx().clone(); let ui = EguiScope::enter(ui); callback.invoke(Some(&ctx), &EguiAnimatedUi::new(&ui, t) as *const _ as *const _) }).response })
     // Bindings for Sides
-    .with(EguiFn::egui_containers_sides_Sides_show, |mut ui: EguiPointer<Ui>, obj: Sides, l_callback: EguiCallback, r_callback: EguiCallback| unsafe { obj.show(ui.get_mut(), |ui| l_callback.invoke(ui as *const _ as *const _), |ui| r_callback.invoke(ui as *const _ as *const _)) })
+    .with(EguiFn::egui_containers_sides_Sides_show, |mut ui: EguiPointer<Ui>, obj: Sides, l_callback: EguiCallback, r_callback: EguiCallback| unsafe { obj.show(ui.get_mut(), |ui| l_callback.invoke_scoped(ui), |ui| r_callback.invoke_scoped(ui)) })
     // Bindings for Slider
     .with(EguiFn::egui_widgets_slider_Slider_ui, |mut ui: EguiPointer<Ui>, obj: SerializableSlider, mut value: f64| unsafe {
         (ui.get_mut().add(obj.to_slider(&mut value)), value)
     })
     // Bindings for SubMenu
-    .with(EguiFn::egui_containers_menu_SubMenu_show, |mut ui: EguiPointer<Ui>, sub_menu: SubMenu, button_response: Response, content: EguiCallback| unsafe { sub_menu.show(ui.get_mut(), &button_response, |ui| content.invoke(ui as *const _ as *const _)).map(|x| x.response) })
+    .with(EguiFn::egui_containers_menu_SubMenu_show, |mut ui: EguiPointer<Ui>, sub_menu: SubMenu, button_response: Response, content: EguiCallback| unsafe { sub_menu.show(ui.get_mut(), &button_response, |ui| content.invoke_scoped(ui)).map(|x| x.response) })
     // Bindings for TextEdit
     .with(EguiFn::egui_widgets_text_edit_builder_TextEdit_ui, |mut ui: EguiPointer<Ui>, obj: SerializableTextEdit, mut text: String, editable: bool| unsafe {
+        let mut layouter = obj.layouter();
+        let layouter = layouter.as_mut().map(|x| x as &mut dyn FnMut(&Ui, &dyn TextBuffer, f32) -> std::sync::Arc<Galley>);
+
         if editable {
-            (ui.get_mut().add(obj.to_text_edit(&mut text)), text)
+            (ui.get_mut().add(obj.to_text_edit(&mut text, layouter)), text)
         }
         else {
-            (ui.get_mut().add(obj.to_text_edit(&mut text.as_str())), String::default())
+            (ui.get_mut().add(obj.to_text_edit(&mut text.as_str(), layouter)), String::default())
         }
     })
     // Bindings for TopBottomPanel
-    .with(EguiFn::egui_containers_panel_TopBottomPanel_show, |ctx: EguiPointer<Context>, panel: TopBottomPanel, callback: EguiCallback| unsafe { panel.show(ctx.get(), |ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_containers_panel_TopBottomPanel_show_inside, |mut ui: EguiPointer<Ui>, panel: TopBottomPanel, callback: EguiCallback| unsafe { panel.show_inside(ui.get_mut(), |ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_containers_panel_TopBottomPanel_show_animated_inside, |mut ui: EguiPointer<Ui>, is_expanded: bool, panel: TopBottomPanel, callback: EguiCallback| unsafe { panel.show_animated_inside(ui.get_mut(), is_expanded, |ui| callback.invoke(ui as *const _ as *const _)).map(|x| x.response) })
-    .with(EguiFn::egui_containers_panel_TopBottomPanel_show_animated, |ctx: EguiPointer<Context>, is_expanded: bool, panel: TopBottomPanel, callback: EguiCallback| unsafe { panel.show_animated(ctx.get(), is_expanded, |ui| callback.invoke(ui as *const _ as *const _)).map(|x| x.response) })
-    .with(EguiFn::egui_containers_panel_TopBottomPanel_show_animated_between, |ctx: EguiPointer<Context>, is_expanded: bool, collapsed_panel: TopBottomPanel, expanded_panel: TopBottomPanel, callback: EguiCallback| unsafe { TopBottomPanel::show_animated_between(ctx.get(), is_expanded, collapsed_panel, expanded_panel, |ui, t| callback.invoke(&EguiAnimatedUi::new(ui, t) as *const _ as *const _)).map(|x| x.response) })
-    .with(EguiFn::egui_containers_panel_TopBottomPanel_show_animated_between_inside, |mut ui: EguiPointer<Ui>, is_expanded: bool, collapsed_panel: TopBottomPanel, expanded_panel: TopBottomPanel, callback: EguiCallback| unsafe { TopBottomPanel::show_animated_between_inside(ui.get_mut(), is_expanded, collapsed_panel, expanded_panel, |ui, t| callback.invoke(&EguiAnimatedUi::new(ui, t) as *const _ as *const _)).response })
+    .with(EguiFn::egui_containers_panel_TopBottomPanel_show_animated_between, |ctx: EguiPointer<Context>, is_expanded: bool, collapsed_panel: TopBottomPanel, expanded_panel: TopBottomPanel, callback: EguiCallback| unsafe { TopBottomPanel::show_animated_between(ctx.get(), is_expanded, collapsed_panel, expanded_panel, |ui, t| { let ctx = ui.ctx().clone(); let ui = EguiScope::enter(ui); callback.invoke(Some(&ctx), &EguiAnimatedUi::new(&ui, t) as *const _ as *const _) }).map(|x| x.response) })
+    .with(EguiFn::egui_containers_panel_TopBottomPanel_show_animated_between_inside, |mut ui: EguiPointer<Ui>, is_expanded: bool, collapsed_panel: TopBottomPanel, expanded_panel: TopBottomPanel, callback: EguiCallback| unsafe { TopBottomPanel::show_animated_between_inside(ui.get_mut(), is_expanded, collapsed_panel, expanded_panel, |ui, t| { let ctx = ui.ctx().clone(); let ui = EguiScope::enter(ui); callback.invoke(Some(&ctx), &EguiAnimatedUi::new(&ui, t) as *const _ as *const _) }).response })
     // Bindings for Tooltip
     .with(EguiFn::egui_containers_tooltip_Tooltip_show, |ctx: EguiPointer<Context>, popup: SerializablePopup, parent_layer: LayerId, parent_widget: Id, callback: EguiCallback| unsafe {
         (Tooltip {
             popup: popup.to_popup(ctx.get(), &mut true),
             parent_layer,
             parent_widget
-        }.show(|ui| callback.invoke(ui as *const _ as *const _)).map(|x| x.response), true)
+        }.show(|ui| callback.invoke_scoped(ui)).map(|x| x.response), true)
     })
     // Bindings for Ui
     .with(EguiFn::egui_ui_Ui_allocate_painter, |mut ui: EguiPointer<Ui>, desired_size: Vec2, sense: Sense| unsafe {
@@ -392,26 +446,31 @@ const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS
     .with(EguiFn::egui_ui_Ui_visuals, |ui: EguiPointer<Ui>| unsafe { ui.get().visuals().clone() })
     .with(EguiFn::egui_ui_Ui_visuals_mut, |mut ui: EguiPointer<Ui>, obj: Visuals| unsafe { *ui.get_mut().visuals_mut() = obj })
     .with(EguiFn::egui_ui_Ui_style_mut, |mut ui: EguiPointer<Ui>, obj: Style| unsafe { *ui.get_mut().style_mut() = obj })
+    .with(EguiFn::egui_ui_Ui_style_patch, |mut ui: EguiPointer<Ui>, field: String, value: Vec<u8>| unsafe { ui.get_mut().style_mut().patch(&field, &value) })
     .with(EguiFn::egui_ui_Ui_spacing_mut, |mut ui: EguiPointer<Ui>, obj: Spacing| unsafe { *ui.get_mut().spacing_mut() = obj })
     .with(EguiFn::egui_ui_Ui_stack, |ui: EguiPointer<Ui>| unsafe { ui.get().stack().clone() })
     .with(EguiFn::egui_ui_Ui_separator, |mut ui: EguiPointer<Ui>| unsafe { ui.get_mut().separator() })
     .with(EguiFn::egui_ui_Ui_set_enabled, |mut ui: EguiPointer<Ui>, enabled: bool| unsafe { ui.get_mut().set_enabled(enabled) })
     .with(EguiFn::egui_ui_Ui_spinner, |mut ui: EguiPointer<Ui>| unsafe { ui.get_mut().spinner() })
-    .with(EguiFn::egui_ui_Ui_group, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().group(|ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_horizontal, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().horizontal(|ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_horizontal_centered, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().horizontal_centered(|ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_horizontal_top, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().horizontal_top(|ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_horizontal_wrapped, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().horizontal_wrapped(|ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_vertical, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().vertical(|ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_vertical_centered, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().vertical_centered(|ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_vertical_centered_justified, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().vertical_centered_justified(|ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_centered_and_justified, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().centered_and_justified(|ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_scope_builder, |mut ui: EguiPointer<Ui>, ui_builder: UiBuilder, callback: EguiCallback| unsafe { ui.get_mut().scope_builder(ui_builder, |ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_indent, |mut ui: EguiPointer<Ui>, id_salt: Id, callback: EguiCallback| unsafe { ui.get_mut().indent(id_salt, |ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_with_visual_transform, |mut ui: EguiPointer<Ui>, transform: TSTransform, callback: EguiCallback| unsafe { ui.get_mut().with_visual_transform(transform, |ui| callback.invoke(ui as *const _ as *const _)).response })
-    .with(EguiFn::egui_ui_Ui_allocate_ui_with_layout, |mut ui: EguiPointer<Ui>, desired_size: Vec2, layout: Layout, callback: EguiCallback| unsafe { ui.get_mut().allocate_ui_with_layout(desired_size, layout, |ui| callback.invoke(ui as *const _ as *const _)).response })
+    .with(EguiFn::egui_ui_Ui_group, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().group(|ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_horizontal, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().horizontal(|ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_horizontal_centered, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().horizontal_centered(|ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_horizontal_top, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().horizontal_top(|ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_horizontal_wrapped, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().horizontal_wrapped(|ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_vertical, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().vertical(|ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_vertical_centered, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().vertical_centered(|ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_vertical_centered_justified, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().vertical_centered_justified(|ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_centered_and_justified, |mut ui: EguiPointer<Ui>, callback: EguiCallback| unsafe { ui.get_mut().centered_and_justified(|ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_scope_builder, |mut ui: EguiPointer<Ui>, ui_builder: UiBuilder, callback: EguiCallback| unsafe { ui.get_mut().scope_builder(ui_builder, |ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_indent, |mut ui: EguiPointer<Ui>, id_salt: Id, callback: EguiCallback| unsafe { ui.get_mut().indent(id_salt, |ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_with_visual_transform, |mut ui: EguiPointer<Ui>, transform: TSTransform, callback: EguiCallback| unsafe { ui.get_mut().with_visual_transform(transform, |ui| callback.invoke_scoped(ui)).response })
+    .with(EguiFn::egui_ui_Ui_allocate_ui_with_layout, |mut ui: EguiPointer<Ui>, desired_size: Vec2, layout: Layout, callback: EguiCallback| unsafe { ui.get_mut().allocate_ui_with_layout(desired_size, layout, |ui| callback.invoke_scoped(ui)).response })
     .with(EguiFn::egui_ui_Ui_columns, |mut ui: EguiPointer<Ui>, num_columns: usize, callback: EguiCallback| unsafe {
-        ui.get_mut().columns(num_columns, |ui_list| callback.invoke(ui_list.iter_mut().map(|x| x as *const _ as *const _).collect::<Vec<*const c_void>>().as_ptr() as *const _))
+        let ctx = ui.get().ctx().clone();
+        ui.get_mut().columns(num_columns, |ui_list| {
+            let scopes = ui_list.iter_mut().map(|x| EguiScope::enter(x)).collect::<Vec<_>>();
+            callback.invoke(Some(&ctx), scopes.iter().map(EguiScope::ptr).collect::<Vec<usize>>().as_ptr() as *const _)
+        })
     })
     // Bindings for WidgetText
     .with(EguiFn::egui_widget_text_WidgetText_text, |x: WidgetText| x.text().to_string())
@@ -419,11 +478,11 @@ const EGUI_FNS: EguiFnMap = AUTOGENERATED_EGUI_FNS
     .with(EguiFn::egui_containers_window_Window_show, |ctx: EguiPointer<Context>, window: SerializableWindow, open: Option<bool>, add_contents: EguiCallback| unsafe {
         let window = Window::from(window);
         if let Some(mut is_open) = open {
-            let response = window.open(&mut is_open).show(ctx.get(), |ui| add_contents.invoke(ui as *const _ as *const _));
+            let response = window.open(&mut is_open).show(ctx.get(), |ui| add_contents.invoke_scoped(ui));
             (response.map(|x| x.response), is_open)
         }
         else {
-            let response = window.show(ctx.get(), |ui| add_contents.invoke(ui as *const _ as *const _));
+            let response = window.show(ctx.get(), |ui| add_contents.invoke_scoped(ui));
             (response.map(|x| x.response), true)
         }
     })
@@ -442,35 +501,89 @@ pub struct EguiAnimatedUi {
 
 impl EguiAnimatedUi {
     /// Creates a new animated UI instance.
-    pub fn new(ui: &mut Ui, t: f32) -> Self {
+    pub fn new(ui: &EguiScope, t: f32) -> Self {
         Self {
-            ui: ui as *const _ as usize,
+            ui: ui.ptr(),
             t
         }
     }
 }
 
-/// A C# callback for `egui` to invoke.
+/// Whether the host completed a callback successfully.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub enum EguiCallbackStatus {
+    /// The callback returned normally.
+    Completed,
+    /// The callback failed, and the host has captured the error.
+    Failed
+}
+
+/// A C# callback for `egui` to invoke. Arguments refer to callbacks by the id
+/// with which they were registered, rather than by address.
 #[derive(Copy, Clone)]
 #[repr(C)]
 pub struct EguiCallback {
     /// The function to call.
-    func: unsafe extern "C" fn(*const c_void, *const c_void),
+    func: unsafe extern "C" fn(*const c_void, *const c_void) -> EguiCallbackStatus,
     /// Data to pass as the second function argument.
-    data: *const c_void
+    data: *const c_void,
+    /// The id with which the callback was registered; see [`callback::egui_callback_register`].
+    id: u64
 }
 
 unsafe impl Send for EguiCallback {}
 unsafe impl Sync for EguiCallback {}
 
 impl EguiCallback {
-    /// Invokes the callback.
+    /// Invokes the callback. If the host fails, then the current pass of `ctx` is discarded,
+    /// since the host's contents are incomplete. Every callback should be invoked through this
+    /// function or [`Self::invoke_scoped`], so that failures are never ignored.
+    /// 
+    /// # Safety
+    /// 
+    /// All invariants for [`Self::func`] must be satisfied.
+    pub unsafe fn invoke(&self, ctx: Option<&Context>, argument: *const c_void) {
+        if !self.try_invoke(argument) {
+            if let Some(ctx) = ctx {
+                ctx.request_discard("A host callback failed");
+            }
+        }
+    }
+
+    /// Invokes the callback, returning whether the host completed it. After a callback fails,
+    /// all further callbacks on this thread are skipped until the enclosing call returns
+    /// the failure to the host; see [`invoke_fn_untraced`].
+    /// 
+    /// # Safety
+    /// 
+    /// All invariants for [`Self::func`] must be satisfied.
+    pub unsafe fn try_invoke(&self, argument: *const c_void) -> bool {
+        if host_failed() {
+            return false;
+        }
+
+        trace::record_callback_enter(argument);
+        let status = (self.func)(argument, self.data);
+        trace::record_callback_exit();
+
+        let completed = status == EguiCallbackStatus::Completed;
+        if !completed {
+            report_host_failure();
+        }
+        completed
+    }
+
+    /// Invokes the callback with a pointer to `value`. The pointer is only
+    /// valid until the callback returns; see [`EguiScope`].
     /// 
     /// # Safety
     /// 
     /// All invariants for [`Self::func`] must be satisfied.
-    pub unsafe fn invoke(&self, argument: *const c_void) {
-        (self.func)(argument, self.data)
+    pub unsafe fn invoke_scoped<T: 'static>(&self, value: *const T) {
+        let ctx = (&*value as &dyn Any).downcast_ref::<Ui>().map(|ui| ui.ctx().clone());
+        let scope = EguiScope::enter(value);
+        self.invoke(ctx.as_ref(), scope.ptr() as *const c_void);
     }
 }
 
@@ -478,7 +591,7 @@ impl Serialize for EguiCallback {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: Serializer {
-        [self.func as usize, self.data as usize].serialize(serializer)
+        self.id.serialize(serializer)
     }
 }
 
@@ -486,18 +599,17 @@ impl<'a> Deserialize<'a> for EguiCallback {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where
             D: Deserializer<'a> {
-        unsafe {
-            let [func, data] = <[usize; 2] as Deserialize<'a>>::deserialize(deserializer)?;
-            Ok(Self {
-                func: transmute(func),
-                data: data as *const _
-            })
+        let id = u64::deserialize(deserializer)?;
+        if let Some(callback) = trace::replay_callback() {
+            return Ok(callback);
         }
+
+        callback::lookup(id).ok_or_else(|| <D::Error as serde::de::Error>::custom(format!("Callback {id:#x} is not registered")))
     }
 }
 
 /// An `egui` object that is passed by reference.
-#[derive(Copy, Clone, Serialize, Deserialize)]
+#[derive(Copy, Clone)]
 #[repr(C)]
 pub struct EguiPointer<T> {
     /// The underlying pointer.
@@ -506,48 +618,99 @@ pub struct EguiPointer<T> {
     marker: PhantomData<*mut T>
 }
 
-impl<T> EguiPointer<T> {
+impl<T> Serialize for EguiPointer<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer {
+        self.ptr.serialize(serializer)
+    }
+}
+
+impl<'a, T> Deserialize<'a> for EguiPointer<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'a> {
+        let ptr = usize::deserialize(deserializer)?;
+        Ok(Self {
+            ptr: trace::map_pointer(ptr).map_err(<D::Error as serde::de::Error>::custom)?,
+            marker: PhantomData
+        })
+    }
+}
+
+impl<T: 'static> EguiPointer<T> {
     /// Gets a reference to the underlying object.
     /// 
     /// # Safety
     /// 
-    /// The pointer must be valid, and no mutable references may exist to it.
+    /// No mutable references may exist to the object. Pointers to scoped objects are checked
+    /// against the live scopes, and all other pointers against the live handles.
+    #[track_caller]
     pub unsafe fn get(&self) -> &T {
-        assert_ne!(self.ptr, 0, "egui object was not initialized");
-        &*(self.ptr as *const _)
+        &*(self.address() as *const _)
     }
 
     /// Gets a mutable reference to the underlying object.
     /// 
     /// # Safety
     /// 
-    /// The pointer must be valid, and no other references may exist to it.
+    /// No other references may exist to the object. Pointers to scoped objects are checked
+    /// against the live scopes, and all other pointers against the live handles.
+    #[track_caller]
     pub unsafe fn get_mut(&mut self) -> &mut T {
-        assert_ne!(self.ptr, 0, "egui object was not initialized");
-        &mut *(self.ptr as *mut _)
+        &mut *(self.address() as *mut _)
+    }
+
+    /// Gets the address of the underlying object, raising an error if it is not live.
+    #[track_caller]
+    fn address(&self) -> usize {
+        if self.ptr == 0 {
+            raise_error(EguiErrorKind::NullPointer, "egui object was not initialized");
+        }
+
+        if scope::is_scoped::<T>() {
+            scope::resolve::<T>(self.ptr)
+        }
+        else {
+            handle::resolve::<T>(self.ptr)
+        }
     }
 }
 
 /// Represents a heap-allocated `egui` object.
-#[derive(Copy, Clone, Serialize, Deserialize)]
+#[derive(Copy, Clone, Deserialize)]
 #[repr(C)]
 pub struct EguiHandle {
     /// A pointer to the object.
     ptr: usize,
-    /// Metadata about the pointer used for internal purposes.
+    /// The serial number and [`handle::EguiHandleKind`] of the object, used to validate drops.
     metadata: usize
 }
 
+impl Serialize for EguiHandle {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer {
+        trace::note_handle(self);
+        (self.ptr, self.metadata).serialize(serializer)
+    }
+}
+
 impl EguiHandle {
     /// Allocates `value` on the heap and returns a handle to it.
     pub fn to_heap<T: 'static>(value: T) -> Self {
         let ptr = Box::into_raw(Box::new(value)) as usize;
-        let metadata = std::ptr::drop_in_place::<T> as usize;
+        let metadata = handle::register::<T>(ptr);
         Self {
             ptr,
             metadata
         }
     }
+
+    /// Gets the address of the object, which the host passes back as an [`EguiPointer`].
+    pub fn ptr(&self) -> usize {
+        self.ptr
+    }
 }
 
 /// Describes the result of an `egui` call.
@@ -557,8 +720,160 @@ pub struct EguiInvokeResult {
     /// Whether `return_value` holds a serialized value or an error string.
     pub success: bool,
     /// If [`Self::success`], then the serialized data that the function returned.
-    /// Otherwise, holds a UTF8 string describing what went wrong.
-    pub return_value: EguiSliceU8
+    /// Otherwise, holds a UTF16 string describing what went wrong.
+    pub return_value: EguiSliceU8,
+    /// If not [`Self::success`], then describes the kind and origin of the error.
+    pub error: EguiInvokeError
+}
+
+impl EguiInvokeResult {
+    /// Releases the buffer holding [`Self::return_value`]; see [`result::egui_release_result`].
+    pub fn release(self) {
+        unsafe { result::egui_release_result(self.return_value); }
+    }
+}
+
+/// Identifies the build of this library. Hosts should compare these values against
+/// the ones with which they were generated.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct EguiAbiInfo {
+    /// The UTF8 version of the `egui_net` crate.
+    pub crate_version: EguiSliceU8,
+    /// The UTF8 version of `egui` for which bindings were generated.
+    pub egui_version: EguiSliceU8,
+    /// The number of [`EguiFn`] variants.
+    pub fn_count: u32,
+    /// A hash of all [`EguiFn`] variant names, in discriminant order.
+    pub fn_names_hash: u64,
+    /// A hash of the reflected `serde` registry used to generate C# types.
+    pub schema_hash: u64
+}
+
+/// Machine-readable information about a failed `egui` call.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct EguiInvokeError {
+    /// The kind of error that occurred.
+    pub kind: EguiErrorKind,
+    /// The [`EguiFn`] discriminant of the failing call, or [`u32::MAX`]
+    /// if the error was not associated with a function.
+    pub func: u32,
+    /// The UTF8 path of the source file that panicked, or an empty slice if unknown.
+    /// This refers to static data and remains valid for the lifetime of the library.
+    pub file: EguiSliceU8,
+    /// The source line that panicked, or zero if unknown.
+    pub line: u32,
+    /// The source column that panicked, or zero if unknown.
+    pub column: u32
+}
+
+impl EguiInvokeError {
+    /// The error value for a successful call.
+    const NONE: Self = Self {
+        kind: EguiErrorKind::None,
+        func: u32::MAX,
+        file: EguiSliceU8::from_slice(&[]),
+        line: 0,
+        column: 0
+    };
+}
+
+/// Identifies why an `egui` call failed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
+pub enum EguiErrorKind {
+    /// The call succeeded.
+    None,
+    /// The requested [`EguiFn`] has no binding.
+    Unbound,
+    /// The arguments could not be decoded, or held invalid values.
+    InvalidArguments,
+    /// The return value could not be encoded.
+    InvalidResult,
+    /// An [`EguiPointer`] was null.
+    NullPointer,
+    /// An [`EguiPointer`] referred to an object that was only lent to the host for
+    /// the duration of a callback, after that callback returned.
+    DanglingPointer,
+    /// `egui` itself panicked.
+    Panic,
+    /// A host callback reported a failure. The host is expected to
+    /// raise the error that it captured when the callback failed.
+    HostError,
+    /// An object was used by one thread while another thread was driving it,
+    /// such as a [`Context`] that is running a pass.
+    ConcurrentAccess
+}
+
+/// Describes why an `egui` call failed. This is the serialized
+/// form of an error within a batch.
+#[derive(Clone, Debug, Serialize)]
+struct EguiError {
+    /// The kind of error that occurred.
+    kind: EguiErrorKind,
+    /// The [`EguiFn`] discriminant of the failing call, or [`u32::MAX`].
+    func: u32,
+    /// A description of what went wrong.
+    message: String,
+    /// The source file that panicked, or an empty string if unknown.
+    file: &'static str,
+    /// The source line that panicked, or zero if unknown.
+    line: u32,
+    /// The source column that panicked, or zero if unknown.
+    column: u32
+}
+
+impl EguiError {
+    /// Creates an error that was not raised by a panic.
+    fn new(kind: EguiErrorKind, func: u32, message: String) -> Self {
+        Self {
+            kind,
+            func,
+            message,
+            file: "",
+            line: 0,
+            column: 0
+        }
+    }
+
+    /// Converts this error to its FFI-compatible form.
+    fn to_ffi(&self) -> EguiInvokeError {
+        EguiInvokeError {
+            kind: self.kind,
+            func: self.func,
+            file: EguiSliceU8::from_slice(self.file.as_bytes()),
+            line: self.line,
+            column: self.column
+        }
+    }
+}
+
+/// A panic payload raised by the bindings layer (rather than by `egui`)
+/// that identifies the kind of failure.
+struct EguiPanic {
+    /// The kind of error that occurred.
+    kind: EguiErrorKind,
+    /// A description of what went wrong.
+    message: String
+}
+
+/// Panics with an [`EguiPanic`] payload, so that the error is reported with the given kind.
+#[track_caller]
+fn raise_error(kind: EguiErrorKind, message: impl Into<String>) -> ! {
+    panic::panic_any(EguiPanic {
+        kind,
+        message: message.into()
+    })
+}
+
+/// A single call within a batch passed to [`egui_invoke_batch`].
+#[derive(Deserialize)]
+struct EguiBatchCall<'a> {
+    /// The [`EguiFn`] discriminant to invoke.
+    func: u32,
+    /// The serialized arguments to the function.
+    args: &'a [u8]
 }
 
 /// Describes a section of a `u8` array.
@@ -615,55 +930,393 @@ impl EguiSliceU8 {
     }
 }
 
+thread_local! {
+    /// Whether a host callback on this thread has failed during the current call,
+    /// or `None` if no call is in progress.
+    static HOST_FAILED: Cell<Option<bool>> = const { Cell::new(None) };
+
+    /// The source location of the most recent panic on this thread.
+    static LAST_PANIC_LOCATION: Cell<Option<(&'static str, u32, u32)>> = const { Cell::new(None) };
+}
+
 /// Initializes the `egui` bindings library.
 #[no_mangle]
 pub unsafe extern "C" fn egui_init() {
-    panic::set_hook(Box::new(|_| ()));
+    panic::set_hook(Box::new(|info| {
+        let location = info.location();
+        if let Some(location) = location {
+            LAST_PANIC_LOCATION.set(Some((location.file(), location.line(), location.column())));
+        }
+
+        if let Some(sink) = log_sink() {
+            if EguiLogLevel::Error <= sink.level {
+                let backtrace = if sink.backtraces { Backtrace::force_capture().to_string() } else { String::new() };
+                sink.emit(EguiLogLevel::Error, "panic", &panic_message(info.payload()),
+                    location.map(|x| x.file()).unwrap_or_default(), location.map(|x| x.line()).unwrap_or_default(), &backtrace);
+            }
+        }
+    }));
 }
 
-/// Invokes a bound `egui` function.
+/// Registers a host callback that receives `log` records and panic messages.
+/// Records less severe than `level` are discarded. If `backtraces` is set, a backtrace
+/// is captured and passed along with every panic.
+/// 
+/// `func` may be called from any thread, and must not unwind.
 #[no_mangle]
-pub unsafe extern "C" fn egui_invoke(f: EguiFn, args: EguiSliceU8) -> EguiInvokeResult {
-    /// The serialization buffer to which results will be written.
-    #[thread_local]
-    static mut RETURN_BUFFER: Vec<u8> = Vec::new();
+pub unsafe extern "C" fn egui_set_log_sink(func: unsafe extern "C" fn(*const EguiLogRecord, *const c_void), data: *const c_void, level: EguiLogLevel, backtraces: bool) {
+    /// The logger that forwards records to the current sink.
+    static LOGGER: EguiLogger = EguiLogger;
 
-    match catch_unwind(|| {
-        if let Some(invoker) = EGUI_FNS.inner[f as usize] {
-            invoker.invoke(f, args.to_ptr(), std::ptr::addr_of_mut!(RETURN_BUFFER));
-            EguiInvokeResult {
-                success: true,
-                return_value: EguiSliceU8::from_slice(&*std::ptr::addr_of_mut!(RETURN_BUFFER))
-            }
+    let _ = log::set_logger(&LOGGER);
+    *LOG_SINK.write().unwrap_or_else(PoisonError::into_inner) = Some(EguiLogSink {
+        func,
+        data,
+        level,
+        backtraces
+    });
+    log::set_max_level(level.to_filter());
+}
+
+/// Removes the callback registered with [`egui_set_log_sink`].
+#[no_mangle]
+pub unsafe extern "C" fn egui_clear_log_sink() {
+    *LOG_SINK.write().unwrap_or_else(PoisonError::into_inner) = None;
+    log::set_max_level(log::LevelFilter::Off);
+}
+
+/// The callback to which log records and panics are currently sent.
+static LOG_SINK: RwLock<Option<EguiLogSink>> = RwLock::new(None);
+
+/// Gets the callback to which log records and panics are currently sent.
+fn log_sink() -> Option<EguiLogSink> {
+    *LOG_SINK.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Extracts a human-readable message from a panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(x) = payload.downcast_ref::<EguiPanic>() {
+        x.message.clone()
+    }
+    else if let Some(x) = payload.downcast_ref::<&'static str>() {
+        x.to_string()
+    }
+    else {
+        payload.downcast_ref::<String>().cloned().unwrap_or_default()
+    }
+}
+
+/// The severity of an [`EguiLogRecord`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(C)]
+pub enum EguiLogLevel {
+    /// No records are emitted. Only valid as a filter.
+    Off,
+    /// Errors and panics.
+    Error,
+    /// Warnings.
+    Warn,
+    /// Informational messages.
+    Info,
+    /// Debugging messages.
+    Debug,
+    /// Very verbose debugging messages.
+    Trace
+}
+
+impl EguiLogLevel {
+    /// Converts this to the equivalent `log` filter.
+    fn to_filter(self) -> log::LevelFilter {
+        match self {
+            Self::Off => log::LevelFilter::Off,
+            Self::Error => log::LevelFilter::Error,
+            Self::Warn => log::LevelFilter::Warn,
+            Self::Info => log::LevelFilter::Info,
+            Self::Debug => log::LevelFilter::Debug,
+            Self::Trace => log::LevelFilter::Trace
         }
-        else {
-            panic!("Function {f:?} not implemented")
+    }
+}
+
+impl From<log::Level> for EguiLogLevel {
+    fn from(value: log::Level) -> Self {
+        match value {
+            log::Level::Error => Self::Error,
+            log::Level::Warn => Self::Warn,
+            log::Level::Info => Self::Info,
+            log::Level::Debug => Self::Debug,
+            log::Level::Trace => Self::Trace
         }
-    }) {
-        Ok(x) => x,
-        Err(error) => {
-            let error_message = error.downcast_ref::<&'static str>()
-                .map(|x| x.to_string())
-                .unwrap_or_else(|| error.downcast_ref::<String>().cloned().unwrap_or_default());
-            
-            let return_buffer = &mut *std::ptr::addr_of_mut!(RETURN_BUFFER);
-            return_buffer.clear();
-            for character in error_message.encode_utf16() {
-                return_buffer.extend(character.to_ne_bytes());
+    }
+}
+
+/// A log message or panic that is passed to the host. All strings are UTF8,
+/// and are only valid for the duration of the callback.
+#[repr(C)]
+pub struct EguiLogRecord {
+    /// The severity of the record.
+    pub level: EguiLogLevel,
+    /// The module path or subsystem that emitted the record. Panics use the target `panic`.
+    pub target: EguiSliceU8,
+    /// The message text.
+    pub message: EguiSliceU8,
+    /// The source file that emitted the record, or an empty slice if unknown.
+    pub file: EguiSliceU8,
+    /// The source line that emitted the record, or zero if unknown.
+    pub line: u32,
+    /// The captured backtrace, or an empty slice if none was captured.
+    pub backtrace: EguiSliceU8
+}
+
+/// A host callback registered with [`egui_set_log_sink`].
+#[derive(Copy, Clone)]
+struct EguiLogSink {
+    /// The function to call.
+    func: unsafe extern "C" fn(*const EguiLogRecord, *const c_void),
+    /// Data to pass as the second function argument.
+    data: *const c_void,
+    /// The least severe level that should be emitted.
+    level: EguiLogLevel,
+    /// Whether to capture backtraces for panics.
+    backtraces: bool
+}
+
+unsafe impl Send for EguiLogSink {}
+unsafe impl Sync for EguiLogSink {}
+
+impl EguiLogSink {
+    /// Passes a record to the host.
+    fn emit(&self, level: EguiLogLevel, target: &str, message: &str, file: &str, line: u32, backtrace: &str) {
+        let record = EguiLogRecord {
+            level,
+            target: EguiSliceU8::from_slice(target.as_bytes()),
+            message: EguiSliceU8::from_slice(message.as_bytes()),
+            file: EguiSliceU8::from_slice(file.as_bytes()),
+            line,
+            backtrace: EguiSliceU8::from_slice(backtrace.as_bytes())
+        };
+
+        unsafe {
+            (self.func)(&record, self.data);
+        }
+    }
+}
+
+/// Forwards records from the `log` crate to the registered [`EguiLogSink`].
+struct EguiLogger;
+
+impl log::Log for EguiLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        log_sink().map(|x| metadata.level() <= x.level.to_filter()).unwrap_or_default()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if let Some(sink) = log_sink() {
+            if record.level() <= sink.level.to_filter() {
+                sink.emit(record.level().into(), record.target(), &record.args().to_string(),
+                    record.file().unwrap_or_default(), record.line().unwrap_or_default(), "");
             }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Invokes a bound `egui` function. The returned value remains valid until it is passed
+/// to [`result::egui_release_result`], even if further calls are made in the meantime.
+#[no_mangle]
+pub unsafe extern "C" fn egui_invoke(f: EguiFn, args: EguiSliceU8) -> EguiInvokeResult {
+    let mut return_buffer = result::acquire();
+    match invoke_fn(f, args.to_ptr(), &mut return_buffer) {
+        Ok(()) => EguiInvokeResult {
+            success: true,
+            return_value: result::lease(return_buffer),
+            error: EguiInvokeError::NONE
+        },
+        Err(error) => error_result(return_buffer, &error)
+    }
+}
 
+/// Invokes a sequence of bound `egui` functions in order. `calls` should hold a serialized
+/// `Vec<EguiBatchCall>`. On success, the result holds a serialized `Vec<Result<Vec<u8>, EguiError>>`
+/// with the return value or error of each call.
+/// 
+/// A failing call does not stop the batch. The functions are invoked exactly as with [`egui_invoke`],
+/// and the result must likewise be released.
+#[no_mangle]
+pub unsafe extern "C" fn egui_invoke_batch(calls: EguiSliceU8) -> EguiInvokeResult {
+    let mut batch_buffer = result::acquire();
+    let calls = match bincode::deserialize::<Vec<EguiBatchCall>>(calls.to_slice()) {
+        Ok(x) => x,
+        Err(error) => return error_result(batch_buffer, &EguiError::new(EguiErrorKind::InvalidArguments, u32::MAX, format!("Failed to decode batch: {error}")))
+    };
+
+    bincode::serialize_into(&mut batch_buffer, &(calls.len() as u64))
+        .expect("Failed to encode batch length");
+
+    let mut call_buffer = result::acquire();
+    for call in calls {
+        let result = match EguiFn::ALL.get(call.func as usize) {
+            Some(f) => invoke_fn(*f, call.args, &mut call_buffer).map(|()| &*call_buffer),
+            None => Err(EguiError::new(EguiErrorKind::Unbound, call.func, format!("Function index {} is out of range", call.func)))
+        };
+
+        bincode::serialize_into(&mut batch_buffer, &result)
+            .expect("Failed to encode batch result");
+    }
+    result::recycle(call_buffer);
+
+    EguiInvokeResult {
+        success: true,
+        return_value: result::lease(batch_buffer),
+        error: EguiInvokeError::NONE
+    }
+}
+
+/// Invokes the bound function `f`, writing its serialized result to `ret`.
+/// Returns a description of the error if the function was unbound or failed.
+/// 
+/// # Safety
+/// 
+/// For this function call to be sound, `args` and `ret` must be valid for the duration
+/// of the call, and all invariants of the bound function must be satisfied.
+unsafe fn invoke_fn(f: EguiFn, args: *const [u8], ret: *mut Vec<u8>) -> Result<(), EguiError> {
+    trace::record_call(f, &*args);
+    let result = invoke_fn_untraced(f, args, ret);
+    trace::record_return(&result, &*ret);
+    result
+}
+
+/// Invokes the bound function `f` without recording it to a trace.
+/// 
+/// # Safety
+/// 
+/// See [`invoke_fn`].
+unsafe fn invoke_fn_untraced(f: EguiFn, args: *const [u8], ret: *mut Vec<u8>) -> Result<(), EguiError> {
+    let Some(invoker) = EGUI_FNS.inner.get(f as usize).copied().flatten() else {
+        return Err(EguiError::new(EguiErrorKind::Unbound, f as u32, format!("Function {f:?} not implemented")));
+    };
+
+    let outer_failed = HOST_FAILED.replace(Some(false));
+    LAST_PANIC_LOCATION.set(None);
+    let result = catch_unwind(|| invoker.invoke(f, args, ret)).map_err(|error| panic_error(f, &*error));
+
+    if HOST_FAILED.replace(outer_failed) == Some(true) {
+        Err(EguiError::new(EguiErrorKind::HostError, f as u32, format!("A host callback failed during {f:?}")))
+    }
+    else {
+        result
+    }
+}
+
+/// Invokes a plain-old-data binding directly, passing `args` to `call` and writing the value
+/// it returns to `ret`. Unlike [`invoke_fn`], no serialization takes place unless a trace is
+/// being recorded, in which case the call is recorded in its serialized form so that it can be
+/// replayed through [`egui_invoke`]. Profiled calls report no argument or result bytes.
+/// 
+/// # Safety
+/// 
+/// `ret` must be valid for writes.
+unsafe fn invoke_pod<A: Serialize, R: Serialize>(f: EguiFn, args: A, ret: *mut R, call: impl FnOnce(A) -> R) -> EguiInvokeResult {
+    let recording = trace::recording();
+    if recording {
+        trace::record_call(f, &bincode::serialize(&args).unwrap_or_default());
+    }
+
+    let start = profile::enabled().then(Instant::now);
+    LAST_PANIC_LOCATION.set(None);
+    let outcome = catch_unwind(AssertUnwindSafe(|| call(args))).map_err(|error| panic_error(f, &*error));
+
+    if let Some(start) = start {
+        profile::record(f, 0, 0, Duration::ZERO, start.elapsed(), Duration::ZERO);
+    }
+
+    if recording {
+        let serialized = outcome.as_ref().ok().and_then(|x| bincode::serialize(x).ok()).unwrap_or_default();
+        trace::record_return(&outcome.as_ref().map(|_| ()).map_err(Clone::clone), &serialized);
+    }
+
+    match outcome {
+        Ok(value) => {
+            ret.write(value);
             EguiInvokeResult {
-                success: false,
-                return_value: EguiSliceU8::from_slice(&return_buffer)
+                success: true,
+                return_value: EguiSliceU8::from_slice(&[]),
+                error: EguiInvokeError::NONE
             }
-        }
+        },
+        Err(error) => error_result(result::acquire(), &error)
     }
 }
 
-/// Frees an `egui` object from the heap.
+/// Whether a host callback or closure has failed during the call in progress on this thread.
+/// Once this happens, no further host code should run until the call returns.
+fn host_failed() -> bool {
+    HOST_FAILED.get() == Some(true)
+}
+
+/// Records that a host callback or closure failed, so that the call in progress on this
+/// thread reports [`EguiErrorKind::HostError`]. Returns `false` if no call is in progress.
+fn report_host_failure() -> bool {
+    let in_call = HOST_FAILED.get().is_some();
+    if in_call {
+        HOST_FAILED.set(Some(true));
+    }
+    in_call
+}
+
+/// Converts a panic that occurred during a call to `f` into an error.
+fn panic_error(f: EguiFn, payload: &(dyn Any + Send)) -> EguiError {
+    let kind = payload.downcast_ref::<EguiPanic>().map(|x| x.kind).unwrap_or(EguiErrorKind::Panic);
+    let (file, line, column) = LAST_PANIC_LOCATION.take().unwrap_or(("", 0, 0));
+    EguiError {
+        kind,
+        func: f as u32,
+        message: panic_message(payload),
+        file,
+        line,
+        column
+    }
+}
+
+/// Writes the message of `error` to `buffer` as a UTF-16 string and returns a failed result which owns it.
+fn error_result(mut buffer: Vec<u8>, error: &EguiError) -> EguiInvokeResult {
+    buffer.clear();
+    for character in error.message.encode_utf16() {
+        buffer.extend(character.to_ne_bytes());
+    }
+
+    EguiInvokeResult {
+        success: false,
+        return_value: result::lease(buffer),
+        error: error.to_ffi()
+    }
+}
+
+/// Describes the interface of this library, so that the host can refuse
+/// to run against a mismatched build.
 #[no_mangle]
-pub unsafe extern "C" fn egui_drop(handle: EguiHandle) {
-    transmute::<_, unsafe fn(usize)>(handle.metadata)(handle.ptr)
+pub unsafe extern "C" fn egui_abi_info() -> EguiAbiInfo {
+    EguiAbiInfo {
+        crate_version: EguiSliceU8::from_slice(env!("CARGO_PKG_VERSION").as_bytes()),
+        egui_version: EguiSliceU8::from_slice(EGUI_VERSION.as_bytes()),
+        fn_count: EguiFn::ALL.len() as u32,
+        fn_names_hash: EGUI_FN_NAMES_HASH,
+        schema_hash: EGUI_SCHEMA_HASH
+    }
+}
+
+/// Frees an `egui` object from the heap. Returns `false` without freeing anything
+/// if the handle was already dropped or was never allocated.
+#[no_mangle]
+pub unsafe extern "C" fn egui_drop(handle: EguiHandle) -> bool {
+    match handle::release(handle) {
+        Ok(()) => true,
+        Err(error) => {
+            log::error!(target: "egui_net", "{error}");
+            false
+        }
+    }
 }
 
 /// Holds bindings for all `egui` functions and allows for translating from [`EguiFn`] names to function pointers.
@@ -674,12 +1327,73 @@ struct EguiFnMap {
 
 impl EguiFnMap {
     /// Adds a function (that has call-by-value semantics) and returns the new map.
-    pub const fn with<A: DeserializeOwned + Tuple, R: Serialize>(mut self, binding: EguiFn, f: impl Copy + Fn<A, Output = R>) -> Self {
+    /// Fails during constant evaluation if `binding` was already added to this map.
+    pub const fn with<A: DeserializeOwned, F: Copy + EguiBinding<A>>(self, binding: EguiFn, f: F) -> Self {
+        if self.inner[binding as usize].is_some() {
+            // The function is bound more than once. Use `with_override` if replacing the earlier binding is intended.
+            panic!("{}", EguiFn::NAMES[binding as usize]);
+        }
+
+        self.with_override(binding, f)
+    }
+
+    /// Adds a function (that has call-by-value semantics), replacing any earlier binding, and returns the new map.
+    pub const fn with_override<A: DeserializeOwned, F: Copy + EguiBinding<A>>(mut self, binding: EguiFn, f: F) -> Self {
         self.inner[binding as usize] = Some(EguiFnInvoker::new(f));
         self
     }
+
+    /// Replaces the entries of this map with every function bound in `other`, and returns the new map.
+    /// This lets hand-written bindings take precedence over autogenerated ones.
+    pub const fn overlay(mut self, other: EguiFnMap) -> Self {
+        let mut i = 0;
+        while i < self.inner.len() {
+            if other.inner[i].is_some() {
+                self.inner[i] = other.inner[i];
+            }
+            i += 1;
+        }
+        self
+    }
+}
+
+/// A function that may be bound to an [`EguiFn`], which takes its arguments as the tuple `A`.
+/// This is implemented for all functions and closures with up to 16 arguments.
+trait EguiBinding<A> {
+    /// The type returned by the function.
+    type Output: Serialize;
+
+    /// Calls the function with the given arguments.
+    fn call(&self, args: A) -> Self::Output;
+}
+
+/// Implements [`EguiBinding`] for functions taking the given argument types.
+macro_rules! impl_egui_binding {
+    () => {
+        impl<F: Fn() -> R, R: Serialize> EguiBinding<()> for F {
+            type Output = R;
+
+            fn call(&self, (): ()) -> R {
+                self()
+            }
+        }
+    };
+    ($first:ident $($rest:ident)*) => {
+        impl<F: Fn($first, $($rest),*) -> R, R: Serialize, $first, $($rest),*> EguiBinding<($first, $($rest),*)> for F {
+            type Output = R;
+
+            #[allow(non_snake_case)]
+            fn call(&self, ($first, $($rest),*): ($first, $($rest),*)) -> R {
+                self($first, $($rest),*)
+            }
+        }
+
+        impl_egui_binding!($($rest)*);
+    };
 }
 
+impl_egui_binding!(A0 A1 A2 A3 A4 A5 A6 A7 A8 A9 A10 A11 A12 A13 A14 A15);
+
 /// Creates a new, empty function map.
 const fn egui_fn_map() -> EguiFnMap {
     EguiFnMap {
@@ -699,7 +1413,7 @@ struct EguiFnInvoker {
 impl EguiFnInvoker {
     /// Stores the provided [`EguiFnInvokable`] on the stack for later use.
     /// `F` should be a `fn` pointer.
-    pub const fn new<A: DeserializeOwned + Tuple, F: Copy + Fn<A, Output: Serialize>>(f: F) -> Self {
+    pub const fn new<A: DeserializeOwned, F: Copy + EguiBinding<A>>(f: F) -> Self {
         unsafe {
             /// Allows for safely reading the data of `f`.
             #[repr(C)]
@@ -739,13 +1453,20 @@ impl EguiFnInvoker {
     /// 
     /// For this function call to be sound, `ptr` must refer to a valid instance
     /// of the invoker function's type. 
-    unsafe fn call_fn<A: DeserializeOwned + Tuple, F: Copy + Fn<A, Output: Serialize>>(fn_id: EguiFn, f: &F, args: *const [u8], ret: *mut Vec<u8>) {
+    unsafe fn call_fn<A: DeserializeOwned, F: Copy + EguiBinding<A>>(fn_id: EguiFn, f: &F, args: *const [u8], ret: *mut Vec<u8>) {
+        let start = profile::enabled().then(Instant::now);
         let deserialized_args = bincode::deserialize(&*args)
-            .unwrap_or_else(|_| panic!("Failed to decode args for call {fn_id:?}"));
-        let result = f.call(deserialized_args);
+            .unwrap_or_else(|_| raise_error(EguiErrorKind::InvalidArguments, format!("Failed to decode args for call {fn_id:?}")));
+        let decoded = start.map(|_| Instant::now());
+        let result = EguiBinding::call(f, deserialized_args);
+        let called = start.map(|_| Instant::now());
         (*ret).clear();
         bincode::serialize_into(&mut *ret, &result)
-            .unwrap_or_else(|_| panic!("Failed to encode result for call {fn_id:?}"));
+            .unwrap_or_else(|_| raise_error(EguiErrorKind::InvalidResult, format!("Failed to encode result for call {fn_id:?}")));
+
+        if let (Some(start), Some(decoded), Some(called)) = (start, decoded, called) {
+            profile::record(fn_id, (*args).len(), (*ret).len(), decoded - start, called - decoded, called.elapsed());
+        }
     }
 }
 
@@ -807,7 +1528,8 @@ struct SerializablePopup {
     pub layout: Layout,
     pub frame: Option<Frame>,
     pub style: Option<Style>,
-    pub menu_style: bool
+    pub menu_style: bool,
+    pub style_modifier: Option<EguiOwnedClosure>
 }
 
 impl SerializablePopup {
@@ -819,7 +1541,7 @@ impl SerializablePopup {
                 1 => OpenKind::Closed,
                 2 => OpenKind::Bool(open_bool),
                 3 => OpenKind::Memory { set: self.open_command.clone() },
-                _ => panic!("Invalid open kind value")
+                _ => raise_error(EguiErrorKind::InvalidArguments, "Invalid open kind value")
             },
             ctx: ctx.clone(),
             anchor: self.anchor,
@@ -837,6 +1559,10 @@ impl SerializablePopup {
             frame: self.frame,
             style: if self.menu_style {
                 StyleModifier::from(menu_style)
+            } else if let Some(modifier) = self.style_modifier.clone() {
+                StyleModifier::new(move |style| if let Some(modified) = modifier.call::<Style, Style>(style) {
+                    *style = modified;
+                })
             } else if let Some(style) = &self.style {
                 style.clone().into()
             }
@@ -901,11 +1627,25 @@ struct SerializableTextEdit {
     pub char_limit: usize,
     pub return_key: Option<KeyboardShortcut>,
     pub background_color: Option<Color32>,
+    pub layouter: Option<EguiOwnedClosure>,
 }
 
 impl SerializableTextEdit {
+    /// Creates the layouter for the [`TextEdit`], if the host provided one. The host maps
+    /// the text and wrap width to a [`LayoutJob`]; if it does not, the text is laid out plainly.
+    pub fn layouter(&self) -> Option<impl FnMut(&Ui, &dyn TextBuffer, f32) -> std::sync::Arc<Galley>> {
+        let layouter = self.layouter.clone()?;
+        let font_selection = self.font_selection.clone();
+
+        Some(move |ui: &Ui, text: &dyn TextBuffer, wrap_width: f32| {
+            let job = layouter.call::<_, LayoutJob>(&(text.as_str(), wrap_width))
+                .unwrap_or_else(|| LayoutJob::simple(text.as_str().to_owned(), font_selection.clone().resolve(ui.style()), ui.visuals().text_color(), wrap_width));
+            ui.fonts(|fonts| fonts.layout_job(job))
+        })
+    }
+
     /// Converts this to a usable [`TextEdit`] widget.
-    pub fn to_text_edit<'a>(self, text: &'a mut impl TextBuffer) -> TextEdit {
+    pub fn to_text_edit<'a>(self, text: &'a mut impl TextBuffer, layouter: Option<&'a mut dyn FnMut(&Ui, &dyn TextBuffer, f32) -> std::sync::Arc<Galley>>) -> TextEdit<'a> {
         TextEdit {
             text,
             hint_text: self.hint_text,
@@ -916,7 +1656,7 @@ impl SerializableTextEdit {
             text_color: self.text_color,
             password: self.password,
             frame: self.frame,
-            layouter: None,
+            layouter,
             margin: self.margin,
             multiline: self.multiline,
             interactive: self.interactive,
@@ -969,7 +1709,7 @@ impl SerializableDragValue {
             2 => result.binary(self.min_width, self.twos_complement),
             8 => result.octal(self.min_width, self.twos_complement),
             16 => result.hexadecimal(self.min_width, self.twos_complement, self.upper),
-            _ => panic!("Unrecognized DragValue parser type")
+            _ => raise_error(EguiErrorKind::InvalidArguments, "Unrecognized DragValue parser type")
         }
     }
 }
@@ -1026,7 +1766,7 @@ impl SerializableSlider {
             2 => result.binary(self.min_width, self.twos_complement),
             8 => result.octal(self.min_width, self.twos_complement),
             16 => result.hexadecimal(self.min_width, self.twos_complement, self.upper),
-            _ => panic!("Unrecognized DragValue parser type")
+            _ => raise_error(EguiErrorKind::InvalidArguments, "Unrecognized DragValue parser type")
         }
     }
 }
@@ -1036,22 +1776,134 @@ impl SerializableSlider {
 mod tests {
     use super::*;
     
-    /// Creates a list of all unbound methods and stubs for them. 
+    /// Writes a report describing how every function is bound, and fails if any function which
+    /// the committed baseline records as bound has become unbound.
     #[test]
-    fn report_unbound_methods() {
-        let mut result = String::new();
-        let mut bound = 0;
+    fn report_coverage() {
+        let mut report = serde_json::from_str::<egui_net_bindgen::CoverageReport>(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../target/bindings/coverage.json")))
+            .expect("Failed to parse coverage report");
 
-        for func in EguiFn::ALL {
-            if EGUI_FNS.inner[*func as usize].is_none() {
-                result += &format!("{func:?}\n");
-            }
-            else {
-                bound += 1;
+        for func in report.fns_mut() {
+            if let Ok(index) = EguiFn::NAMES.binary_search(&&*func.id) {
+                if MANUAL_EGUI_FNS.inner[index].is_some() {
+                    func.status = egui_net_bindgen::FnCoverageStatus::Manual;
+                    func.reason = None;
+                }
             }
         }
 
-        result = format!("{bound} / {} ({}%) bound\n{result}", EguiFn::ALL.len(), (100.0 * bound as f32 / EguiFn::ALL.len() as f32).floor());
-        std::fs::write("progress_report.txt", result).expect("Failed to write progress report");
+        let json = serde_json::to_string_pretty(&report).expect("Failed to serialize coverage report");
+        std::fs::write("coverage_report.json", json).expect("Failed to write coverage report");
+
+        let baseline = serde_json::from_str::<CoverageBaseline>(include_str!("../coverage_baseline.json"))
+            .expect("Failed to parse coverage baseline");
+        let regressed = EguiFn::ALL.iter().zip(EguiFn::NAMES)
+            .filter(|(func, name)| EGUI_FNS.inner[**func as usize].is_none() && !baseline.unbound.iter().any(|x| x == *name))
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>();
+        assert!(regressed.is_empty(), "Functions are no longer bound (see coverage_report.json): {regressed:?}");
+    }
+
+    /// The functions which were unbound when the baseline was last updated.
+    #[derive(Deserialize)]
+    struct CoverageBaseline {
+        /// The names of the unbound functions.
+        unbound: Vec<String>
+    }
+
+    /// Invokes several functions through a single batch, including one that fails.
+    #[test]
+    fn invoke_batch() {
+        let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(2.0, 4.0));
+        let calls = vec![
+            (EguiFn::emath_rect_Rect_center as u32, bincode::serialize(&rect).expect("Failed to encode args")),
+            (u32::MAX, Vec::new())
+        ];
+        let bytes = bincode::serialize(&calls).expect("Failed to encode batch");
+
+        let result = unsafe { egui_invoke_batch(EguiSliceU8::from_slice(&bytes)) };
+        assert!(result.success);
+
+        let results = bincode::deserialize::<Vec<Result<Vec<u8>, (EguiErrorKind, u32, String, String, u32, u32)>>>(unsafe { result.return_value.to_slice() })
+            .expect("Failed to decode batch results");
+        assert_eq!(results.len(), 2);
+        assert_eq!(bincode::deserialize::<Pos2>(results[0].as_ref().expect("Call failed")).ok(), Some(pos2(1.0, 2.0)));
+        assert_eq!(results[1].as_ref().err().map(|x| x.0), Some(EguiErrorKind::Unbound));
+        result.release();
+        assert_eq!(result::outstanding(), 0);
+    }
+
+    /// Invokes a plain-old-data binding directly and through the serialized path.
+    #[test]
+    fn invoke_pod() {
+        let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(2.0, 4.0));
+        let mut expanded = Rect::ZERO;
+        assert!(unsafe { egui_pod_emath_rect_Rect_expand(rect, 1.0, &mut expanded) }.success);
+        assert_eq!(expanded, rect.expand(1.0));
+
+        let args = bincode::serialize(&(rect, 1.0f32)).expect("Failed to encode args");
+        let result = unsafe { egui_invoke(EguiFn::emath_rect_Rect_expand, EguiSliceU8::from_slice(&args)) };
+        assert!(result.success);
+        assert_eq!(bincode::deserialize::<Rect>(unsafe { result.return_value.to_slice() }).ok(), Some(expanded));
+        result.release();
+    }
+
+    /// Ensures that a failed host callback skips further callbacks and is reported to the host.
+    #[test]
+    fn host_errors_abort_calls() {
+        thread_local! {
+            /// The number of times [`failing_callback`] has been called.
+            static CALLS: Cell<u32> = const { Cell::new(0) };
+        }
+
+        /// Fails every time that it is called.
+        unsafe extern "C" fn failing_callback(_: *const c_void, _: *const c_void) -> EguiCallbackStatus {
+            CALLS.set(CALLS.get() + 1);
+            EguiCallbackStatus::Failed
+        }
+
+        let ctx = EguiHandle::to_heap(Context::default());
+        let callback = unsafe { callback::egui_callback_register(failing_callback, std::ptr::null()) };
+        let args = bincode::serialize(&(ctx.ptr, RawInput::default(), callback)).expect("Failed to encode args");
+
+        let result = unsafe { egui_invoke(EguiFn::egui_context_Context_run, EguiSliceU8::from_slice(&args)) };
+        assert!(!result.success);
+        assert_eq!(result.error.kind, EguiErrorKind::HostError);
+        assert_eq!(CALLS.get(), 1);
+        assert_eq!(HOST_FAILED.get(), None);
+        result.release();
+
+        assert!(unsafe { callback::egui_callback_unregister(callback) });
+        assert!(unsafe { egui_drop(ctx) });
+    }
+
+    /// Ensures that a failed row callback of [`ScrollArea::show_rows`] fails the call and discards the pass.
+    #[test]
+    fn host_errors_discard_rows() {
+        /// Fails every time that it is called.
+        unsafe extern "C" fn failing_callback(_: *const c_void, _: *const c_void) -> EguiCallbackStatus {
+            EguiCallbackStatus::Failed
+        }
+
+        let ctx = Context::default();
+        let callback = unsafe { callback::egui_callback_register(failing_callback, std::ptr::null()) };
+        let mut discarded = false;
+
+        let _ = ctx.run(RawInput::default(), |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                let scope = EguiScope::enter(ui as *const Ui);
+                let args = bincode::serialize(&(scope.ptr(), ScrollArea::vertical(), 10.0f32, 100usize, callback)).expect("Failed to encode args");
+                let result = unsafe { egui_invoke(EguiFn::egui_containers_scroll_area_ScrollArea_show_rows, EguiSliceU8::from_slice(&args)) };
+                assert!(!result.success);
+                assert_eq!(result.error.kind, EguiErrorKind::HostError);
+                result.release();
+
+                drop(scope);
+                discarded |= ui.ctx().will_discard();
+            });
+        });
+
+        assert!(discarded);
+        assert!(unsafe { callback::egui_callback_unregister(callback) });
     }
-}
\ No newline at end of file
+}
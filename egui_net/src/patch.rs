@@ -0,0 +1,88 @@
+//! Allows the host to replace individual fields of large `egui` structures,
+//! rather than round-tripping the entire structure for every change.
+
+use super::*;
+
+/// A type whose fields may be replaced individually by the host.
+pub trait EguiPatch {
+    /// Replaces the field named `field` with the serialized `value`. Names are compared
+    /// without regard to case or underscores, so both `zoom_factor` and `ZoomFactor` match.
+    /// An error is raised if the field does not exist or the value could not be decoded.
+    fn patch(&mut self, field: &str, value: &[u8]);
+}
+
+/// Implements [`EguiPatch`] for types with the given public fields.
+macro_rules! impl_patch {
+    ($($ty:ty { $($field:ident),* $(,)? })*) => {
+        $(
+            impl EguiPatch for $ty {
+                fn patch(&mut self, field: &str, value: &[u8]) {
+                    $(
+                        if field_matches(stringify!($field), field) {
+                            self.$field = decode_field(stringify!($ty), field, value);
+                            return;
+                        }
+                    )*
+
+                    raise_error(EguiErrorKind::InvalidArguments, format!("{} has no patchable field {field}", stringify!($ty)));
+                }
+            }
+        )*
+    };
+}
+
+impl_patch! {
+    InputState {
+        raw, pointer, raw_scroll_delta, smooth_scroll_delta, screen_rect, pixels_per_point, max_texture_side,
+        time, unstable_dt, predicted_dt, stable_dt, focused, modifiers, keys_down, events
+    }
+
+    Options {
+        theme_preference, fallback_theme, zoom_factor, zoom_with_keyboard, tessellation_options,
+        repaint_on_widget_change, max_passes, screen_reader, warn_on_id_clash, input_options, reduce_texture_memory
+    }
+
+    Style {
+        override_text_style, override_font_id, override_text_valign, text_styles, drag_value_text_style, wrap_mode,
+        spacing, interaction, visuals, animation_time, debug, explanation_tooltips, url_in_tooltip,
+        always_scroll_the_only_direction, scroll_animation
+    }
+
+    TessellationOptions {
+        feathering, feathering_size_in_pixels, coarse_tessellation_culling, prerasterized_discs, round_text_to_pixels,
+        debug_paint_clip_rects, debug_paint_text_rects, debug_ignore_clip_rects, bezier_tolerance, epsilon,
+        parallel_tessellation, validate_meshes
+    }
+}
+
+/// Whether the Rust field `name` is referred to by the host as `given`.
+fn field_matches(name: &str, given: &str) -> bool {
+    let normalize = |x: &str| x.bytes().filter(|b| *b != b'_').map(|b| b.to_ascii_lowercase()).collect::<Vec<_>>();
+    normalize(name) == normalize(given)
+}
+
+/// Decodes the new value of `field` on type `ty`, raising an error if it is invalid.
+#[track_caller]
+fn decode_field<T: DeserializeOwned>(ty: &str, field: &str, value: &[u8]) -> T {
+    bincode::deserialize(value).unwrap_or_else(|error| raise_error(EguiErrorKind::InvalidArguments, format!("Invalid value for {ty}.{field}: {error}")))
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensures that a single field is replaced and that unknown fields are rejected.
+    #[test]
+    fn patches_single_field() {
+        let mut options = Options::default();
+        let repaint = options.repaint_on_widget_change;
+
+        options.patch("ZoomFactor", &bincode::serialize(&2.0f32).expect("Failed to encode value"));
+        assert_eq!(options.zoom_factor, 2.0);
+        assert_eq!(options.repaint_on_widget_change, repaint);
+
+        let error = catch_unwind(AssertUnwindSafe(|| options.patch("NoSuchField", &[]))).expect_err("Unknown field was accepted");
+        assert_eq!(error.downcast_ref::<EguiPanic>().map(|x| x.kind), Some(EguiErrorKind::InvalidArguments));
+    }
+}
@@ -0,0 +1,175 @@
+//! Optional per-function counters for the FFI dispatcher, used to find
+//! which bindings dominate frame time.
+
+use super::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Whether counters are currently being accumulated.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The counters for each [`EguiFn`], indexed by discriminant.
+static COUNTERS: [EguiFnCounters; EguiFn::ALL.len()] = [EguiFnCounters::ZERO; EguiFn::ALL.len()];
+
+/// Enables or disables accumulation of per-function counters. Counters are disabled by default.
+#[no_mangle]
+pub unsafe extern "C" fn egui_profile_set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Gets the counters of every function that has been called since the last reset.
+/// The returned data remains valid until the next snapshot is taken on the same thread.
+#[no_mangle]
+pub unsafe extern "C" fn egui_profile_snapshot() -> EguiProfileSnapshot {
+    thread_local! {
+        /// The buffer to which the snapshot will be written.
+        static SNAPSHOT_BUFFER: UnsafeCell<Vec<EguiFnProfile>> = const { UnsafeCell::new(Vec::new()) };
+    }
+
+    let buffer = &mut *SNAPSHOT_BUFFER.with(UnsafeCell::get);
+    buffer.clear();
+    buffer.extend(EguiFn::ALL.iter().zip(&COUNTERS)
+        .map(|(f, counters)| counters.load(*f))
+        .filter(|x| x.calls > 0));
+
+    EguiProfileSnapshot {
+        ptr: buffer.as_ptr(),
+        len: buffer.len()
+    }
+}
+
+/// Resets all per-function counters to zero.
+#[no_mangle]
+pub unsafe extern "C" fn egui_profile_reset() {
+    for counters in &COUNTERS {
+        counters.reset();
+    }
+}
+
+/// Whether per-function counters should be accumulated.
+#[inline(always)]
+pub(crate) fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Adds a single call of `f` to the counters.
+pub(crate) fn record(f: EguiFn, arg_bytes: usize, result_bytes: usize, decode: Duration, call: Duration, encode: Duration) {
+    let counters = &COUNTERS[f as usize];
+    counters.calls.fetch_add(1, Ordering::Relaxed);
+    counters.arg_bytes.fetch_add(arg_bytes as u64, Ordering::Relaxed);
+    counters.result_bytes.fetch_add(result_bytes as u64, Ordering::Relaxed);
+    counters.decode_ns.fetch_add(decode.as_nanos() as u64, Ordering::Relaxed);
+    counters.call_ns.fetch_add(call.as_nanos() as u64, Ordering::Relaxed);
+    counters.encode_ns.fetch_add(encode.as_nanos() as u64, Ordering::Relaxed);
+}
+
+/// The accumulated counters for a single function.
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct EguiFnProfile {
+    /// The [`EguiFn`] discriminant.
+    pub func: u32,
+    /// The number of times the function was called.
+    pub calls: u64,
+    /// The total size of all serialized arguments.
+    pub arg_bytes: u64,
+    /// The total size of all serialized results.
+    pub result_bytes: u64,
+    /// The total time spent decoding arguments, in nanoseconds.
+    pub decode_ns: u64,
+    /// The total time spent executing the function, in nanoseconds.
+    /// This includes the time spent in nested calls and host callbacks.
+    pub call_ns: u64,
+    /// The total time spent encoding results, in nanoseconds.
+    pub encode_ns: u64
+}
+
+/// A list of [`EguiFnProfile`]s returned by [`egui_profile_snapshot`].
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct EguiProfileSnapshot {
+    /// A pointer to the first profile.
+    pub ptr: *const EguiFnProfile,
+    /// The number of profiles.
+    pub len: usize
+}
+
+/// Atomically-updated counters for a single function.
+struct EguiFnCounters {
+    /// See [`EguiFnProfile::calls`].
+    calls: AtomicU64,
+    /// See [`EguiFnProfile::arg_bytes`].
+    arg_bytes: AtomicU64,
+    /// See [`EguiFnProfile::result_bytes`].
+    result_bytes: AtomicU64,
+    /// See [`EguiFnProfile::decode_ns`].
+    decode_ns: AtomicU64,
+    /// See [`EguiFnProfile::call_ns`].
+    call_ns: AtomicU64,
+    /// See [`EguiFnProfile::encode_ns`].
+    encode_ns: AtomicU64
+}
+
+impl EguiFnCounters {
+    /// A set of counters that are all zero.
+    const ZERO: Self = Self {
+        calls: AtomicU64::new(0),
+        arg_bytes: AtomicU64::new(0),
+        result_bytes: AtomicU64::new(0),
+        decode_ns: AtomicU64::new(0),
+        call_ns: AtomicU64::new(0),
+        encode_ns: AtomicU64::new(0)
+    };
+
+    /// Reads the current values of the counters.
+    fn load(&self, f: EguiFn) -> EguiFnProfile {
+        EguiFnProfile {
+            func: f as u32,
+            calls: self.calls.load(Ordering::Relaxed),
+            arg_bytes: self.arg_bytes.load(Ordering::Relaxed),
+            result_bytes: self.result_bytes.load(Ordering::Relaxed),
+            decode_ns: self.decode_ns.load(Ordering::Relaxed),
+            call_ns: self.call_ns.load(Ordering::Relaxed),
+            encode_ns: self.encode_ns.load(Ordering::Relaxed)
+        }
+    }
+
+    /// Sets all counters to zero.
+    fn reset(&self) {
+        for counter in [&self.calls, &self.arg_bytes, &self.result_bytes, &self.decode_ns, &self.call_ns, &self.encode_ns] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensures that serialized and plain-old-data calls are counted while profiling is enabled.
+    #[test]
+    fn counts_calls() {
+        let args = bincode::serialize(&Rect::from_min_max(pos2(0.0, 0.0), pos2(2.0, 4.0))).expect("Failed to encode args");
+
+        unsafe {
+            egui_profile_set_enabled(true);
+            for _ in 0..2 {
+                let result = egui_invoke(EguiFn::emath_rect_Rect_center, EguiSliceU8::from_slice(&args));
+                assert!(result.success);
+                result.release();
+            }
+
+            let mut expanded = Rect::ZERO;
+            assert!(egui_pod_emath_rect_Rect_expand(Rect::ZERO, 1.0, &mut expanded).success);
+
+            let snapshot = egui_profile_snapshot();
+            let profiles = std::slice::from_raw_parts(snapshot.ptr, snapshot.len);
+            let profile = profiles.iter().find(|x| x.func == EguiFn::emath_rect_Rect_center as u32)
+                .expect("Function was not profiled");
+
+            assert!(profile.calls >= 2);
+            assert!(profile.arg_bytes >= 2 * args.len() as u64);
+            assert!(profiles.iter().any(|x| x.func == EguiFn::emath_rect_Rect_expand as u32));
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Manages the buffers which hold the results returned to the host. Every result is written to
+//! its own buffer, which stays valid until the host passes it to [`egui_release_result`]. Calls
+//! made from within callbacks therefore never overwrite a result that the host is still reading.
+
+use super::*;
+use std::cell::RefCell;
+
+/// The largest number of released buffers that each thread keeps for reuse.
+const MAX_FREE_BUFFERS: usize = 16;
+
+thread_local! {
+    /// The buffers which hold results that the host has not yet released.
+    static LEASED: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+
+    /// Released buffers, which may be reused for later results.
+    static FREE: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Releases a result returned by [`egui_invoke`], [`egui_invoke_batch`], or a plain-old-data
+/// binding, so that its buffer may be reused. The host should call this once it has finished
+/// reading the result, from the same thread that made the call. Returns `false` if the result
+/// did not own a buffer, which is the case for empty results and results that were already released.
+#[no_mangle]
+pub unsafe extern "C" fn egui_release_result(value: EguiSliceU8) -> bool {
+    let Some(buffer) = LEASED.with_borrow_mut(|leased| leased.iter()
+        .rposition(|x| x.as_ptr() == value.ptr)
+        .map(|index| leased.swap_remove(index))) else {
+        return false;
+    };
+
+    recycle(buffer);
+    true
+}
+
+/// Gets an empty buffer to which a result may be written.
+pub(crate) fn acquire() -> Vec<u8> {
+    FREE.with_borrow_mut(Vec::pop).unwrap_or_default()
+}
+
+/// Returns a buffer obtained from [`acquire`] without handing it to the host.
+pub(crate) fn recycle(mut buffer: Vec<u8>) {
+    buffer.clear();
+    FREE.with_borrow_mut(|free| if free.len() < MAX_FREE_BUFFERS {
+        free.push(buffer);
+    });
+}
+
+/// Hands a buffer obtained from [`acquire`] to the host, returning a slice which remains valid
+/// until it is passed to [`egui_release_result`].
+pub(crate) fn lease(buffer: Vec<u8>) -> EguiSliceU8 {
+    let value = EguiSliceU8::from_slice(&buffer);
+    if buffer.capacity() > 0 {
+        LEASED.with_borrow_mut(|leased| leased.push(buffer));
+    }
+    value
+}
+
+/// Gets the number of results on this thread which have not yet been released.
+pub fn outstanding() -> usize {
+    LEASED.with_borrow(Vec::len)
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The number of nested [`Context::fonts`] calls to make.
+    const NEST_DEPTH: u32 = 24;
+
+    /// The state shared by the callbacks of [`results_survive_nested_calls`].
+    #[derive(Default)]
+    struct NestState {
+        /// The context being run.
+        ctx: usize,
+        /// The id of [`nest_fonts`], which calls itself.
+        fonts_callback: Cell<u64>,
+        /// The [`Ui`] of the central panel.
+        ui: Cell<usize>,
+        /// The current nesting depth.
+        depth: Cell<u32>,
+        /// The greatest nesting depth reached.
+        max_depth: Cell<u32>
+    }
+
+    /// Invokes `f` with serialized `args`.
+    unsafe fn invoke(f: EguiFn, args: &impl Serialize) -> EguiInvokeResult {
+        let args = bincode::serialize(args).expect("Failed to encode args");
+        egui_invoke(f, EguiSliceU8::from_slice(&args))
+    }
+
+    /// Converts whether a nested call succeeded into a callback status.
+    fn status(completed: bool) -> EguiCallbackStatus {
+        match completed {
+            true => EguiCallbackStatus::Completed,
+            false => EguiCallbackStatus::Failed
+        }
+    }
+
+    /// Shows a central panel, which calls [`nest_ui`].
+    unsafe extern "C" fn show_panel(_: *const c_void, state: *const c_void) -> EguiCallbackStatus {
+        let state = &*(state as *const NestState);
+        let ui_callback = callback::egui_callback_register(nest_ui, state as *const NestState as *const c_void);
+        let result = invoke(EguiFn::egui_containers_panel_CentralPanel_show, &(state.ctx, CentralPanel::default(), ui_callback));
+        callback::egui_callback_unregister(ui_callback);
+        let completed = result.success;
+        result.release();
+        status(completed)
+    }
+
+    /// Records the panel's [`Ui`] and then begins the nested font calls.
+    unsafe extern "C" fn nest_ui(ui: *const c_void, state: *const c_void) -> EguiCallbackStatus {
+        let state = &*(state as *const NestState);
+        state.ui.set(ui as usize);
+        status(nest_fonts(std::ptr::null(), state as *const NestState as *const c_void) == EguiCallbackStatus::Completed)
+    }
+
+    /// Obtains the painter of the panel, and then, while still holding that result, calls
+    /// [`Context::fonts`] with itself as the callback until [`NEST_DEPTH`] is reached.
+    unsafe extern "C" fn nest_fonts(_: *const c_void, state: *const c_void) -> EguiCallbackStatus {
+        let state = &*(state as *const NestState);
+        let depth = state.depth.get();
+        state.max_depth.set(state.max_depth.get().max(depth));
+
+        let held = invoke(EguiFn::egui_ui_Ui_painter, &state.ui.get());
+        if !held.success {
+            held.release();
+            return EguiCallbackStatus::Failed;
+        }
+        let expected = held.return_value.to_slice().to_vec();
+
+        let mut completed = true;
+        if depth < NEST_DEPTH {
+            state.depth.set(depth + 1);
+            let inner = invoke(EguiFn::egui_context_Context_fonts, &(state.ctx, state.fonts_callback.get()));
+            state.depth.set(depth);
+            completed = inner.success;
+            inner.release();
+        }
+
+        completed &= held.return_value.to_slice() == expected;
+        completed &= bincode::deserialize::<EguiHandle>(held.return_value.to_slice()).is_ok_and(|painter| egui_drop(painter));
+        completed &= egui_release_result(held.return_value);
+        status(completed)
+    }
+
+    /// Ensures that results held by the host remain intact while deeply nested calls are made
+    /// from callbacks, and that every result is released afterward.
+    #[test]
+    fn results_survive_nested_calls() {
+        let ctx = EguiHandle::to_heap(Context::default());
+        let state = NestState {
+            ctx: ctx.ptr(),
+            ..Default::default()
+        };
+
+        unsafe {
+            let data = &state as *const NestState as *const c_void;
+            state.fonts_callback.set(callback::egui_callback_register(nest_fonts, data));
+            let run_callback = callback::egui_callback_register(show_panel, data);
+
+            let result = invoke(EguiFn::egui_context_Context_run, &(ctx.ptr(), RawInput::default(), run_callback));
+            assert!(result.success, "Run failed with {:?}", result.error.kind);
+            result.release();
+
+            callback::egui_callback_unregister(run_callback);
+            callback::egui_callback_unregister(state.fonts_callback.get());
+            assert!(egui_drop(ctx));
+        }
+
+        assert_eq!(state.max_depth.get(), NEST_DEPTH);
+        assert_eq!(outstanding(), 0);
+    }
+
+    /// Ensures that a result cannot be released twice, and that empty results own no buffer.
+    #[test]
+    fn releases_each_result_once() {
+        let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(2.0, 4.0));
+        let first = unsafe { invoke(EguiFn::emath_rect_Rect_center, &rect) };
+        let second = unsafe { invoke(EguiFn::emath_rect_Rect_center, &rect) };
+        assert_ne!(first.return_value.ptr, second.return_value.ptr);
+        assert_eq!(outstanding(), 2);
+
+        assert!(unsafe { egui_release_result(first.return_value) });
+        assert!(!unsafe { egui_release_result(first.return_value) });
+        assert!(unsafe { egui_release_result(second.return_value) });
+        assert!(!unsafe { egui_release_result(EguiSliceU8::from_slice(&[])) });
+        assert_eq!(outstanding(), 0);
+    }
+}
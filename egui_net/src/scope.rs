@@ -0,0 +1,128 @@
+//! Tracks the lifetimes of objects which are lent to the host during callbacks, so that
+//! using them after the callback returns raises an error instead of causing undefined behavior.
+
+use super::*;
+use std::any::TypeId;
+use std::cell::RefCell;
+
+/// The number of low bits in a scoped pointer which hold the address. On 64-bit targets,
+/// the remaining bits hold the generation of the scope.
+#[cfg(target_pointer_width = "64")]
+const ADDRESS_BITS: u32 = 48;
+
+/// The number of low bits in a scoped pointer which hold the address. On 64-bit targets,
+/// the remaining bits hold the generation of the scope.
+#[cfg(not(target_pointer_width = "64"))]
+const ADDRESS_BITS: u32 = usize::BITS;
+
+/// The mask used to extract the address from a scoped pointer.
+const ADDRESS_MASK: usize = if ADDRESS_BITS == usize::BITS { usize::MAX } else { (1 << ADDRESS_BITS) - 1 };
+
+thread_local! {
+    /// The scopes that are currently live on this thread, from outermost to innermost.
+    static LIVE_SCOPES: RefCell<Vec<ScopeEntry>> = const { RefCell::new(Vec::new()) };
+
+    /// The generation to assign to the next scope on this thread.
+    static NEXT_GENERATION: Cell<usize> = const { Cell::new(1) };
+}
+
+/// Marks an object as live for the host. While this guard exists, [`Self::ptr`] may be
+/// passed to the host and resolved again with [`resolve`]. Dropping the guard invalidates the pointer.
+pub struct EguiScope {
+    /// The tagged pointer to the object.
+    ptr: usize
+}
+
+impl EguiScope {
+    /// Registers `value` as live until the returned scope is dropped.
+    pub fn enter<T: 'static>(value: *const T) -> Self {
+        let generation = NEXT_GENERATION.get();
+        NEXT_GENERATION.set(generation.wrapping_add(1));
+
+        let address = value as usize;
+        assert!(address & !ADDRESS_MASK == 0, "Address of scoped object exceeded {ADDRESS_BITS} bits");
+
+        let ptr = address | generation.checked_shl(ADDRESS_BITS).unwrap_or(0);
+        LIVE_SCOPES.with_borrow_mut(|scopes| scopes.push(ScopeEntry { ty: TypeId::of::<T>(), ptr }));
+        Self { ptr }
+    }
+
+    /// Gets the tagged pointer that should be passed to the host.
+    pub fn ptr(&self) -> usize {
+        self.ptr
+    }
+}
+
+impl Drop for EguiScope {
+    fn drop(&mut self) {
+        LIVE_SCOPES.with_borrow_mut(|scopes| {
+            if let Some(index) = scopes.iter().rposition(|x| x.ptr == self.ptr) {
+                scopes.remove(index);
+            }
+        });
+    }
+}
+
+/// Gets the tagged pointer of the innermost live scope for `value`, raising an error if there is none.
+#[track_caller]
+pub fn lookup<T: 'static>(value: *const T) -> usize {
+    LIVE_SCOPES.with_borrow(|scopes| scopes.iter().rev()
+            .find(|x| x.ty == TypeId::of::<T>() && x.ptr & ADDRESS_MASK == value as usize)
+            .map(|x| x.ptr))
+        .unwrap_or_else(|| raise_error(EguiErrorKind::DanglingPointer, format!("{} is not live", std::any::type_name::<T>())))
+}
+
+/// Converts a pointer received from the host into an address. If `T` is lent to the host
+/// through scopes, then an error is raised unless the pointer belongs to a live scope.
+#[track_caller]
+pub fn resolve<T: 'static>(ptr: usize) -> usize {
+    if !is_scoped::<T>() {
+        return ptr;
+    }
+
+    if LIVE_SCOPES.with_borrow(|scopes| scopes.iter().any(|x| x.ty == TypeId::of::<T>() && x.ptr == ptr)) {
+        ptr & ADDRESS_MASK
+    }
+    else {
+        raise_error(EguiErrorKind::DanglingPointer, format!("{} was used after the callback that provided it returned", std::any::type_name::<T>()))
+    }
+}
+
+/// Whether pointers to `T` are only valid within a scope.
+pub(crate) fn is_scoped<T: 'static>() -> bool {
+    let ty = TypeId::of::<T>();
+    ty == TypeId::of::<Ui>() || ty == TypeId::of::<Memory>() || ty == TypeId::of::<Fonts>()
+        || ty == TypeId::of::<EguiCollapsingStateShowToggleButtonParams<'static>>()
+}
+
+/// Describes a live scope.
+struct ScopeEntry {
+    /// The type of the scoped object.
+    ty: TypeId,
+    /// The tagged pointer to the object.
+    ptr: usize
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensures that scoped pointers are rejected once their scope ends.
+    #[test]
+    fn rejects_stale_pointers() {
+        let memory = Memory::default();
+        let stale = EguiScope::enter(&memory).ptr();
+
+        let scope = EguiScope::enter(&memory);
+        let live = scope.ptr();
+        assert_eq!(resolve::<Memory>(live), &memory as *const _ as usize);
+        assert_eq!(lookup(&memory), live);
+        drop(scope);
+
+        for ptr in [stale, live] {
+            let error = catch_unwind(|| resolve::<Memory>(ptr)).expect_err("Stale pointer was accepted");
+            assert_eq!(error.downcast_ref::<EguiPanic>().map(|x| x.kind), Some(EguiErrorKind::DanglingPointer));
+        }
+    }
+}
@@ -0,0 +1,581 @@
+//! Records every `egui_invoke` call made on a thread to a trace file, and replays such traces
+//! against a fresh `egui` context so that host bugs can be reproduced without the host.
+
+use super::*;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Identifies the start of a trace file.
+const TRACE_MAGIC: [u8; 4] = *b"EGTR";
+
+/// The version of the trace file format.
+const TRACE_VERSION: u32 = 3;
+
+/// Functions whose callback argument is a `#[repr(C)]` struct that begins with a [`Ui`] pointer.
+const UI_STRUCT_ARGUMENT_FNS: &[EguiFn] = &[
+    EguiFn::egui_containers_panel_SidePanel_show_animated_between,
+    EguiFn::egui_containers_panel_SidePanel_show_animated_between_inside,
+    EguiFn::egui_containers_panel_TopBottomPanel_show_animated_between,
+    EguiFn::egui_containers_panel_TopBottomPanel_show_animated_between_inside,
+    EguiFn::egui_containers_scroll_area_ScrollArea_show_rows,
+    EguiFn::egui_containers_scroll_area_ScrollArea_show_viewport
+];
+
+/// Functions whose result begins with a raw address, which differs between runs.
+const ADDRESS_RESULT_FNS: &[EguiFn] = &[
+    EguiFn::egui_context_Context_ref_id,
+    EguiFn::egui_containers_collapsing_header_EguiCollapsingStateShowToggleButtonParams_unpack
+];
+
+thread_local! {
+    /// The recorder for the current thread, if a trace is being recorded.
+    static RECORDER: RefCell<Option<TraceRecorder>> = const { RefCell::new(None) };
+
+    /// The replayer for the current thread, if a trace is being replayed.
+    static REPLAYER: RefCell<Option<TraceReplayer>> = const { RefCell::new(None) };
+
+    /// Handles that have been serialized as part of a result since the last call returned.
+    static HANDLE_NOTES: RefCell<Vec<EguiHandle>> = const { RefCell::new(Vec::new()) };
+
+    /// Whether a trace is being recorded or replayed on this thread.
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Begins recording every call made on the current thread to the file at `path`,
+/// which should be a UTF8 string. Returns `false` if the file could not be created.
+#[no_mangle]
+pub unsafe extern "C" fn egui_trace_begin(path: EguiSliceU8) -> bool {
+    let Ok(path) = std::str::from_utf8(path.to_slice()) else { return false };
+    let Ok(file) = File::create(path) else { return false };
+
+    let mut writer = BufWriter::new(file);
+    let header = TraceHeader {
+        magic: TRACE_MAGIC,
+        version: TRACE_VERSION,
+        fn_names_hash: EGUI_FN_NAMES_HASH
+    };
+
+    if bincode::serialize_into(&mut writer, &header).is_err() {
+        return false;
+    }
+
+    RECORDER.set(Some(TraceRecorder {
+        writer,
+        call_stack: Vec::new()
+    }));
+    ACTIVE.set(true);
+    true
+}
+
+/// Stops the recording started by [`egui_trace_begin`] and flushes the trace file.
+#[no_mangle]
+pub unsafe extern "C" fn egui_trace_end() {
+    if let Some(mut recorder) = RECORDER.take() {
+        let _ = recorder.writer.flush();
+    }
+
+    ACTIVE.set(REPLAYER.with_borrow(Option::is_some));
+}
+
+/// Replays the trace at `path` against a fresh `egui` context, and reports
+/// every place where the results differ from the recording.
+pub fn replay(path: impl AsRef<Path>) -> std::io::Result<ReplayReport> {
+    let events = read_trace(path)?;
+
+    REPLAYER.set(Some(TraceReplayer {
+        events,
+        position: 0,
+        pointers: HashMap::new(),
+        report: ReplayReport::default()
+    }));
+    ACTIVE.set(true);
+
+    while let Some(event) = with_replayer(|r| r.peek().cloned()).flatten() {
+        if let TraceEvent::Call { .. } = event {
+            replay_call();
+        }
+        else {
+            with_replayer(|r| {
+                r.diverge(None, format!("Unexpected top-level event {event:?}"));
+                r.position += 1;
+            });
+        }
+    }
+
+    let replayer = REPLAYER.take().expect("Replayer was removed during replay");
+    ACTIVE.set(RECORDER.with_borrow(Option::is_some));
+    Ok(replayer.report)
+}
+
+/// Records the start of a call to `f`.
+pub(crate) fn record_call(f: EguiFn, args: &[u8]) {
+    if ACTIVE.get() {
+        with_recorder(|r| {
+            r.call_stack.push(f);
+            r.write(&TraceEvent::Call { func: f as u32, args: args.to_vec() });
+        });
+    }
+}
+
+/// Records the result of the innermost call. `ret` holds the serialized result if the call succeeded.
+pub(crate) fn record_return(result: &Result<(), EguiError>, ret: &[u8]) {
+    if ACTIVE.get() {
+        with_recorder(|r| {
+            r.call_stack.pop();
+            r.write(&TraceEvent::Return {
+                result: result.as_ref().map(|()| ret.to_vec()).map_err(|e| (e.kind, e.message.clone())),
+                handles: take_handle_notes()
+            });
+
+            if r.call_stack.is_empty() {
+                let _ = r.writer.flush();
+            }
+        });
+    }
+}
+
+/// Records that a callback is about to be invoked with `argument`.
+///
+/// # Safety
+///
+/// If the innermost call is in [`UI_STRUCT_ARGUMENT_FNS`], then `argument` must
+/// point to a struct that begins with a pointer.
+pub(crate) unsafe fn record_callback_enter(argument: *const c_void) {
+    if ACTIVE.get() {
+        with_recorder(|r| {
+            let struct_argument = r.call_stack.last()
+                .is_some_and(|f| UI_STRUCT_ARGUMENT_FNS.iter().any(|x| *x as u32 == *f as u32));
+            let inner = (struct_argument && !argument.is_null()).then(|| *(argument as *const usize));
+            r.write(&TraceEvent::CallbackEnter { argument: argument as usize, inner });
+        });
+    }
+}
+
+/// Records that a callback has returned.
+pub(crate) fn record_callback_exit() {
+    if ACTIVE.get() {
+        with_recorder(|r| r.write(&TraceEvent::CallbackExit));
+    }
+}
+
+/// Records the raw input of `ffi` when recording, or restores it from the trace when replaying.
+pub(crate) fn sync_ffi_input(ffi: &mut EguiFfi) {
+    if ACTIVE.get() {
+        with_recorder(|r| r.write(&TraceEvent::FfiInput {
+            raw_input: bincode::serialize(&ffi.raw_input()).expect("Failed to serialize RawInput"),
+            viewport_raw_inputs: bincode::serialize(&ffi.viewport_raw_inputs()).expect("Failed to serialize viewport RawInput")
+        }));
+
+        if let Some(Some((raw_input, viewport_raw_inputs))) = with_replayer(TraceReplayer::next_ffi_input) {
+            ffi.set_raw_input(raw_input);
+            ffi.set_viewport_raw_inputs(viewport_raw_inputs);
+        }
+    }
+}
+
+/// Notes that `handle` was serialized as part of a result, so that
+/// recorded handles can be matched with live ones during replay.
+pub(crate) fn note_handle(handle: &EguiHandle) {
+    if ACTIVE.get() {
+        HANDLE_NOTES.with_borrow_mut(|notes| notes.push(*handle));
+    }
+}
+
+/// Translates a pointer received from the host. During replay, recorded
+/// addresses are mapped to their live equivalents.
+pub(crate) fn map_pointer(ptr: usize) -> Result<usize, String> {
+    if ptr == 0 || !ACTIVE.get() {
+        return Ok(ptr);
+    }
+
+    with_replayer(|r| r.pointers.get(&ptr).copied()
+        .ok_or_else(|| format!("Address {ptr:#x} was not produced earlier in the trace")))
+        .unwrap_or(Ok(ptr))
+}
+
+/// During replay, gets the callback that should be used in place of a recorded one.
+pub(crate) fn replay_callback() -> Option<EguiCallback> {
+    if ACTIVE.get() {
+        with_replayer(|_| EguiCallback {
+            func: replay_callback_fn,
+            data: std::ptr::null(),
+            id: 0
+        })
+    }
+    else {
+        None
+    }
+}
+
+/// Whether a trace is being replayed on this thread.
+pub(crate) fn replaying() -> bool {
+    ACTIVE.get() && REPLAYER.with_borrow(Option::is_some)
+}
+
+/// Whether a trace is being recorded on this thread.
+pub(crate) fn recording() -> bool {
+    ACTIVE.get() && RECORDER.with_borrow(Option::is_some)
+}
+
+/// Runs `f` with the current recorder, if any.
+fn with_recorder<R>(f: impl FnOnce(&mut TraceRecorder) -> R) -> Option<R> {
+    RECORDER.with_borrow_mut(|x| x.as_mut().map(f))
+}
+
+/// Runs `f` with the current replayer, if any.
+fn with_replayer<R>(f: impl FnOnce(&mut TraceReplayer) -> R) -> Option<R> {
+    REPLAYER.with_borrow_mut(|x| x.as_mut().map(f))
+}
+
+/// Takes all handles noted since the last call returned.
+fn take_handle_notes() -> Vec<(usize, usize)> {
+    HANDLE_NOTES.take().into_iter().map(|x| (x.ptr, x.metadata)).collect()
+}
+
+/// Replays the [`TraceEvent::Call`] at the current position, along with all nested events.
+fn replay_call() {
+    let Some((index, func, args)) = with_replayer(|r| {
+        let index = r.position;
+        r.position += 1;
+        match &r.events[index] {
+            TraceEvent::Call { func, args } => Some((index, *func, args.clone())),
+            _ => None
+        }
+    }).flatten() else { return };
+
+    let Some(f) = EguiFn::ALL.get(func as usize).copied() else {
+        with_replayer(|r| {
+            r.diverge(Some(index), format!("Function index {func} is out of range"));
+            r.skip_to_return();
+        });
+        return;
+    };
+
+    let mut ret = Vec::new();
+    let result = unsafe { invoke_fn(f, &args[..], &mut ret) };
+    let live_handles = take_handle_notes();
+
+    with_replayer(|r| r.finish_call(index, f, result, ret, live_handles));
+}
+
+/// The callback passed to bound functions during replay. Replays
+/// all calls that were made inside the recorded callback.
+unsafe extern "C" fn replay_callback_fn(argument: *const c_void, _: *const c_void) -> EguiCallbackStatus {
+    let Some((recorded, inner)) = with_replayer(TraceReplayer::next_callback_enter).flatten() else { return EguiCallbackStatus::Completed };
+
+    with_replayer(|r| {
+        r.pointers.insert(recorded, argument as usize);
+        if let Some(inner) = inner {
+            if !argument.is_null() {
+                r.pointers.insert(inner, *(argument as *const usize));
+            }
+        }
+    });
+
+    loop {
+        match with_replayer(|r| r.peek().cloned()).flatten() {
+            Some(TraceEvent::Call { .. }) => replay_call(),
+            Some(TraceEvent::CallbackExit) => {
+                with_replayer(|r| r.position += 1);
+                break;
+            },
+            _ => {
+                with_replayer(|r| r.diverge(None, "Callback returned earlier than recorded".to_string()));
+                break;
+            }
+        }
+    }
+
+    EguiCallbackStatus::Completed
+}
+
+/// Reads all events from the trace file at `path`.
+fn read_trace(path: impl AsRef<Path>) -> std::io::Result<Vec<TraceEvent>> {
+    let invalid_data = |x: bincode::Error| std::io::Error::new(std::io::ErrorKind::InvalidData, x);
+
+    let mut reader = BufReader::new(File::open(path)?);
+    let header = bincode::deserialize_from::<_, TraceHeader>(&mut reader).map_err(invalid_data)?;
+    if header.magic != TRACE_MAGIC || header.version != TRACE_VERSION {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "File is not a supported trace"));
+    }
+
+    if header.fn_names_hash != EGUI_FN_NAMES_HASH {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Trace was recorded with a different set of bindings"));
+    }
+
+    let mut events = Vec::new();
+    loop {
+        match bincode::deserialize_from::<_, TraceEvent>(&mut reader) {
+            Ok(event) => events.push(event),
+            Err(error) => match &*error {
+                bincode::ErrorKind::Io(x) if x.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                _ => return Err(invalid_data(error))
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+/// Replaces the first occurrence of `from` in `bytes` with `to`.
+fn replace_bytes(bytes: &mut [u8], from: &[u8], to: &[u8]) {
+    if let Some(index) = bytes.windows(from.len()).position(|x| x == from) {
+        bytes[index..index + from.len()].copy_from_slice(to);
+    }
+}
+
+/// Describes the contents of a trace file.
+#[derive(Serialize, Deserialize)]
+struct TraceHeader {
+    /// Always [`TRACE_MAGIC`].
+    magic: [u8; 4],
+    /// The [`TRACE_VERSION`] with which the file was written.
+    version: u32,
+    /// The [`EGUI_FN_NAMES_HASH`] of the library that recorded the trace.
+    fn_names_hash: u64
+}
+
+/// A single entry in a trace file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum TraceEvent {
+    /// A bound function was invoked.
+    Call {
+        /// The [`EguiFn`] discriminant.
+        func: u32,
+        /// The serialized arguments.
+        args: Vec<u8>
+    },
+    /// The innermost function returned.
+    Return {
+        /// The serialized return value, or the kind and message of the error.
+        result: Result<Vec<u8>, (EguiErrorKind, String)>,
+        /// The handles contained in the return value, in serialization order.
+        handles: Vec<(usize, usize)>
+    },
+    /// A host callback was invoked by the innermost function.
+    CallbackEnter {
+        /// The argument passed to the callback.
+        argument: usize,
+        /// The [`Ui`] pointer at the start of the argument, for functions in [`UI_STRUCT_ARGUMENT_FNS`].
+        inner: Option<usize>
+    },
+    /// The innermost host callback returned.
+    CallbackExit,
+    /// The raw input of an [`EguiFfi`] was consumed.
+    FfiInput {
+        /// The serialized [`RawInput`] of the root viewport.
+        raw_input: Vec<u8>,
+        /// The serialized [`RawInput`] of every other viewport.
+        viewport_raw_inputs: Vec<u8>
+    }
+}
+
+/// Writes events to a trace file.
+struct TraceRecorder {
+    /// The file to which events are written.
+    writer: BufWriter<File>,
+    /// The functions that are currently executing, innermost last.
+    call_stack: Vec<EguiFn>
+}
+
+impl TraceRecorder {
+    /// Appends an event to the trace.
+    fn write(&mut self, event: &TraceEvent) {
+        let _ = bincode::serialize_into(&mut self.writer, event);
+    }
+}
+
+/// Holds the state of an ongoing replay.
+struct TraceReplayer {
+    /// All events in the trace.
+    events: Vec<TraceEvent>,
+    /// The index of the next event to replay.
+    position: usize,
+    /// Maps recorded addresses to live ones.
+    pointers: HashMap<usize, usize>,
+    /// The report being accumulated.
+    report: ReplayReport
+}
+
+impl TraceReplayer {
+    /// Gets the next event to replay.
+    fn peek(&self) -> Option<&TraceEvent> {
+        self.events.get(self.position)
+    }
+
+    /// Records a divergence at `event`, or the current position if unspecified.
+    fn diverge(&mut self, event: Option<usize>, message: String) {
+        let event = event.unwrap_or(self.position);
+        let func = self.enclosing_fn(event);
+        self.report.divergences.push(Divergence {
+            event,
+            func,
+            message
+        });
+    }
+
+    /// Gets the name of the function that was called at or before `event`.
+    fn enclosing_fn(&self, event: usize) -> String {
+        self.events[..(event + 1).min(self.events.len())].iter().rev()
+            .find_map(|x| if let TraceEvent::Call { func, .. } = x { Some(*func) } else { None })
+            .map(|x| EguiFn::ALL.get(x as usize).map(|f| format!("{f:?}")).unwrap_or_else(|| x.to_string()))
+            .unwrap_or_default()
+    }
+
+    /// Consumes the next event if it is a [`TraceEvent::CallbackEnter`].
+    fn next_callback_enter(&mut self) -> Option<(usize, Option<usize>)> {
+        if let Some(TraceEvent::CallbackEnter { argument, inner }) = self.peek() {
+            let result = (*argument, *inner);
+            self.position += 1;
+            Some(result)
+        }
+        else {
+            self.diverge(None, "Callback was invoked more often than recorded".to_string());
+            None
+        }
+    }
+
+    /// Consumes the next event if it is a [`TraceEvent::FfiInput`].
+    fn next_ffi_input(&mut self) -> Option<(RawInput, ViewportIdMap<RawInput>)> {
+        if let Some(TraceEvent::FfiInput { raw_input, viewport_raw_inputs }) = self.peek() {
+            let result = bincode::deserialize(raw_input).ok()
+                .zip(bincode::deserialize(viewport_raw_inputs).ok());
+            self.position += 1;
+            result
+        }
+        else {
+            self.diverge(None, "Raw input was consumed more often than recorded".to_string());
+            None
+        }
+    }
+
+    /// Skips all nested events up to and including the [`TraceEvent::Return`] of the current call.
+    /// Returns the skipped return event, if any.
+    fn skip_to_return(&mut self) -> Option<TraceEvent> {
+        let mut depth = 0usize;
+        while let Some(event) = self.events.get(self.position).cloned() {
+            self.position += 1;
+            match event {
+                TraceEvent::Call { .. } | TraceEvent::CallbackEnter { .. } => depth += 1,
+                TraceEvent::CallbackExit => depth = depth.saturating_sub(1),
+                TraceEvent::Return { .. } if depth == 0 => return Some(event),
+                TraceEvent::Return { .. } => depth -= 1,
+                TraceEvent::FfiInput { .. } => {}
+            }
+        }
+
+        None
+    }
+
+    /// Compares the live result of the call at `index` with the recorded one.
+    fn finish_call(&mut self, index: usize, f: EguiFn, result: Result<(), EguiError>, ret: Vec<u8>, live_handles: Vec<(usize, usize)>) {
+        self.report.calls += 1;
+
+        if !matches!(self.peek(), Some(TraceEvent::Return { .. })) {
+            self.diverge(Some(index), "Call made fewer callbacks than recorded".to_string());
+        }
+
+        let Some(TraceEvent::Return { result: recorded, handles }) = self.skip_to_return() else {
+            self.diverge(Some(index), "Trace ended before the call returned".to_string());
+            return;
+        };
+
+        match (recorded, result) {
+            (Ok(mut recorded), Ok(())) => {
+                if handles.len() != live_handles.len() {
+                    self.diverge(Some(index), format!("Returned {} handles, but {} were recorded", live_handles.len(), handles.len()));
+                }
+
+                for (old, new) in handles.iter().zip(&live_handles) {
+                    self.pointers.insert(old.0, new.0);
+                    let old_bytes = bincode::serialize(old).expect("Failed to serialize handle");
+                    let new_bytes = bincode::serialize(new).expect("Failed to serialize handle");
+                    replace_bytes(&mut recorded, &old_bytes, &new_bytes);
+                }
+
+                if ADDRESS_RESULT_FNS.iter().any(|x| *x as u32 == f as u32) && recorded.len() >= size_of::<u64>() && ret.len() >= size_of::<u64>() {
+                    let old = u64::from_le_bytes(recorded[..8].try_into().expect("Slice had wrong length"));
+                    let new = u64::from_le_bytes(ret[..8].try_into().expect("Slice had wrong length"));
+                    self.pointers.insert(old as usize, new as usize);
+                    recorded[..8].copy_from_slice(&ret[..8]);
+                }
+
+                if recorded != ret {
+                    self.diverge(Some(index), format!("Result differs: recorded {recorded:?}, replayed {ret:?}"));
+                }
+            },
+            (Ok(_), Err(error)) => self.diverge(Some(index), format!("Call failed during replay: {:?}: {}", error.kind, error.message)),
+            (Err((kind, message)), Ok(())) => self.diverge(Some(index), format!("Call succeeded during replay, but failed when recorded: {kind:?}: {message}")),
+            (Err((kind, _)), Err(error)) => if kind != error.kind {
+                self.diverge(Some(index), format!("Call failed with {:?}, but {kind:?} was recorded", error.kind));
+            }
+        }
+    }
+}
+
+/// Summarizes the outcome of [`replay`].
+#[derive(Clone, Debug, Default)]
+pub struct ReplayReport {
+    /// The number of calls that were replayed.
+    pub calls: usize,
+    /// All places where the replay differed from the recording.
+    pub divergences: Vec<Divergence>
+}
+
+/// Describes a place where a replay differed from the recording.
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    /// The index of the trace event at which the divergence was detected.
+    pub event: usize,
+    /// The name of the function that was executing.
+    pub func: String,
+    /// A description of the difference.
+    pub message: String
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "event {} ({}): {}", self.event, self.func, self.message)
+    }
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Invokes `f` with serialized `args`, and deserializes the result.
+    unsafe fn invoke<R: DeserializeOwned>(f: EguiFn, args: &impl Serialize) -> R {
+        let args = bincode::serialize(args).expect("Failed to encode args");
+        let result = egui_invoke(f, EguiSliceU8::from_slice(&args));
+        assert!(result.success);
+        let value = bincode::deserialize(result.return_value.to_slice()).expect("Failed to decode result");
+        result.release();
+        value
+    }
+
+    /// Records a short trace, including a plain-old-data call, and ensures that it replays without divergence.
+    #[test]
+    fn replay_matches_recording() {
+        let path = std::env::temp_dir().join(format!("egui_net_trace_{}.bin", std::process::id()));
+        let path_str = path.to_str().expect("Temporary path was not UTF8");
+
+        unsafe {
+            assert!(egui_trace_begin(EguiSliceU8::from_slice(path_str.as_bytes())));
+            let ctx = invoke::<EguiHandle>(EguiFn::egui_context_Context_default, &());
+            invoke::<usize>(EguiFn::egui_context_Context_ref_count, &ctx.ptr);
+            invoke::<Pos2>(EguiFn::emath_rect_Rect_center, &Rect::from_min_max(pos2(0.0, 0.0), pos2(2.0, 4.0)));
+            let mut expanded = Rect::ZERO;
+            assert!(egui_pod_emath_rect_Rect_expand(Rect::from_min_max(pos2(0.0, 0.0), pos2(2.0, 4.0)), 1.0, &mut expanded).success);
+            egui_trace_end();
+        }
+
+        let report = replay(&path).expect("Failed to replay trace");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(report.calls, 4);
+        assert!(report.divergences.is_empty(), "{:?}", report.divergences);
+    }
+}
@@ -0,0 +1,200 @@
+//! Runs the immediate and deferred viewports of a [`Context`] on behalf of [`EguiFfi`],
+//! so that the host may show each viewport in its own native window.
+
+use super::*;
+use std::cell::RefCell;
+
+thread_local! {
+    /// The run of [`run_ffi`] that is in progress on this thread, if any.
+    static PASS: RefCell<Option<ViewportPass>> = const { RefCell::new(None) };
+}
+
+/// The state of a call to [`run_ffi`].
+struct ViewportPass {
+    /// The raw input of each viewport other than the root.
+    raw_inputs: ViewportIdMap<RawInput>,
+    /// The output of each viewport that has run, in the order in which the runs ended.
+    outputs: Vec<(ViewportId, FullOutput)>
+}
+
+/// Restores the pass that was in progress before a call to [`run_ffi`], even if a viewport fails.
+struct PassGuard(Option<ViewportPass>);
+
+impl Drop for PassGuard {
+    fn drop(&mut self) {
+        PASS.set(self.0.take());
+    }
+}
+
+/// Runs the root viewport of `ctx` with the input of `ffi`, followed by every deferred viewport
+/// for which the host supplied input. Immediate viewports are run as they are shown. The combined
+/// output is written to `ffi`, along with the meshes or shapes of each viewport.
+///
+/// # Safety
+///
+/// `ffi` must remain valid for the duration of the call, and must not be accessed
+/// mutably from `run_ui`.
+pub(crate) unsafe fn run_ffi(ctx: &Context, mut ffi: EguiPointer<EguiFfi>, run_ui: impl FnMut(&Context)) {
+    let raw_input = ffi.get().raw_input();
+    let _guard = PassGuard(PASS.replace(Some(ViewportPass {
+        raw_inputs: ffi.get().viewport_raw_inputs(),
+        outputs: Vec::new()
+    })));
+    // egui cannot uninstall a renderer, so outside of this call `run_immediate` embeds viewports instead
+    Context::set_immediate_viewport_renderer(run_immediate);
+
+    let root_output = ctx.run(raw_input, run_ui);
+    let deferred = root_output.viewport_output.iter()
+        .filter_map(|(id, output)| output.viewport_ui_cb.clone().map(|callback| (*id, callback)))
+        .collect::<Vec<_>>();
+    push_output(ViewportId::ROOT, root_output);
+
+    for (viewport_id, callback) in deferred {
+        let has_input = PASS.with_borrow(|pass| pass.as_ref().is_some_and(|x| x.raw_inputs.contains_key(&viewport_id)));
+        if has_input {
+            run_viewport(ctx, viewport_id, |ctx| callback(ctx));
+        }
+    }
+
+    let pass = PASS.take().expect("Viewport pass was removed");
+    let mut full_output = FullOutput::default();
+    let mut root_pixels_per_point = 1.0;
+    let mut shapes = Vec::with_capacity(pass.outputs.len());
+    for (viewport_id, mut output) in pass.outputs {
+        let pixels_per_point = output.pixels_per_point;
+        let screen_rect = ctx.input_for(viewport_id, |i| i.screen_rect());
+        shapes.push((viewport_id, take(&mut output.shapes), pixels_per_point, screen_rect));
+
+        if viewport_id == ViewportId::ROOT {
+            root_pixels_per_point = pixels_per_point;
+        }
+
+        full_output.append(output);
+    }
+    full_output.pixels_per_point = root_pixels_per_point;
+
+    let ffi = ffi.get_mut();
+    ffi.set_full_output(full_output);
+    match ffi.paint_mode() {
+        FfiPaintMode::Meshes => ffi.set_viewport_primitives(shapes.into_iter()
+            .map(|(viewport_id, shapes, pixels_per_point, screen_rect)| (viewport_id, ctx.tessellate(shapes, pixels_per_point), pixels_per_point, screen_rect))),
+        FfiPaintMode::Shapes => ffi.set_viewport_shapes(shapes.into_iter()
+            .map(|(viewport_id, shapes, pixels_per_point, _)| (viewport_id, shapes, pixels_per_point)))
+    }
+    ffi.set_viewport_raw_inputs(ViewportIdMap::default());
+}
+
+/// Shows an immediate viewport. Outside of [`run_ffi`], the viewport is embedded in its parent,
+/// as egui does when no immediate viewport renderer is installed.
+pub(crate) fn show_immediate<T>(ctx: &Context, id: ViewportId, builder: ViewportBuilder, mut viewport_ui: impl FnMut(&Context, ViewportClass) -> T) -> T {
+    if PASS.with_borrow(Option::is_some) {
+        ctx.show_viewport_immediate(id, builder, viewport_ui)
+    }
+    else {
+        viewport_ui(ctx, ViewportClass::Embedded)
+    }
+}
+
+/// Runs an immediate viewport that was shown during [`run_ffi`]. Viewports shown on this
+/// thread after [`run_ffi`] has returned are drawn into their parent instead.
+fn run_immediate(ctx: &Context, viewport: ImmediateViewport<'_>) {
+    let ImmediateViewport { ids, mut viewport_ui_cb, .. } = viewport;
+    if PASS.with_borrow(Option::is_some) {
+        run_viewport(ctx, ids.this, |ctx| viewport_ui_cb(ctx));
+    }
+    else {
+        viewport_ui_cb(ctx);
+    }
+}
+
+/// Runs a viewport other than the root with the input that the host supplied for it.
+/// Events are only delivered to the first run of each viewport.
+fn run_viewport(ctx: &Context, viewport_id: ViewportId, run_ui: impl FnMut(&Context)) {
+    let mut raw_input = PASS.with_borrow_mut(|pass| pass.as_mut()
+        .and_then(|x| x.raw_inputs.get_mut(&viewport_id))
+        .map(RawInput::take))
+        .unwrap_or_default();
+    raw_input.viewport_id = viewport_id;
+    raw_input.viewports.entry(viewport_id).or_default();
+
+    let output = ctx.run(raw_input, run_ui);
+    push_output(viewport_id, output);
+}
+
+/// Records the output of a viewport. Output from repeated runs of the same viewport is combined.
+/// Output produced outside of [`run_ffi`] is discarded.
+fn push_output(viewport_id: ViewportId, output: FullOutput) {
+    PASS.with_borrow_mut(|pass| if let Some(pass) = pass {
+        match pass.outputs.iter_mut().find(|(id, _)| *id == viewport_id) {
+            Some((_, existing)) => existing.append(output),
+            None => pass.outputs.push((viewport_id, output))
+        }
+    });
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensures that immediate viewports, and deferred viewports with input, are run and
+    /// tessellated separately from the root viewport, and that their input is consumed.
+    #[test]
+    fn runs_child_viewports() {
+        let ctx = Context::default();
+        ctx.set_embed_viewports(false);
+        let immediate = ViewportId::from_hash_of("immediate");
+        let deferred = ViewportId::from_hash_of("deferred");
+
+        let handle = EguiHandle::to_heap(EguiFfi::default());
+        let pointer = || EguiPointer::<EguiFfi> { ptr: handle.ptr(), marker: PhantomData };
+
+        unsafe {
+            pointer().get_mut().set_viewport_raw_inputs([(deferred, RawInput::default())].into_iter().collect());
+            run_ffi(&ctx, pointer(), |ctx| {
+                ctx.show_viewport_immediate(immediate, ViewportBuilder::default(), |ctx, _| {
+                    CentralPanel::default().show(ctx, |ui| ui.label("Immediate"));
+                });
+                ctx.show_viewport_deferred(deferred, ViewportBuilder::default(), |ctx, _| {
+                    CentralPanel::default().show(ctx, |ui| ui.label("Deferred"));
+                });
+            });
+
+            let ffi = pointer();
+            let output = ffi.get().full_output();
+            assert!(output.viewport_output.contains_key(&immediate));
+            assert!(output.viewport_output.contains_key(&deferred));
+            assert_eq!(ffi.get().viewport_frames().map(|(id, _)| id).collect::<Vec<_>>(), [ViewportId::ROOT, immediate, deferred]);
+            assert!(ffi.get().viewport_frame(deferred).is_some_and(|x| !x.meshes().is_empty()));
+            assert!(ffi.get().viewport_raw_inputs().is_empty());
+            assert!(egui_drop(handle));
+        }
+    }
+
+    /// Ensures that once [`run_ffi`] has returned, immediate viewports shown by a plain
+    /// [`Context::run`] on the same thread are embedded in their parent, as they were before.
+    #[test]
+    fn embeds_immediate_viewports_after_run_ffi() {
+        let ctx = Context::default();
+        ctx.set_embed_viewports(false);
+        let immediate = ViewportId::from_hash_of("immediate");
+
+        let handle = EguiHandle::to_heap(EguiFfi::default());
+        unsafe {
+            run_ffi(&ctx, EguiPointer { ptr: handle.ptr(), marker: PhantomData }, |_| {});
+            assert!(egui_drop(handle));
+        }
+
+        let mut class = None;
+        let output = ctx.run(RawInput::default(), |ctx| {
+            show_immediate(ctx, immediate, ViewportBuilder::default(), |ctx, x| {
+                class = Some(x);
+                CentralPanel::default().show(ctx, |ui| ui.label("Immediate"));
+            });
+        });
+
+        assert!(class == Some(ViewportClass::Embedded));
+        assert!(!output.viewport_output.contains_key(&immediate));
+        assert!(!output.shapes.is_empty());
+    }
+}
@@ -1,6 +1,3 @@
-#![feature(formatting_options)]
-#![feature(let_chains)]
-
 use convert_case::*;
 use egui::*;
 use egui::color_picker::*;
@@ -68,12 +65,30 @@ const BINDING_EXCLUDE_FNS: &[&str] = &[
     "egui_ui_Ui_separator",
     "egui_ui_Ui_spinner",
 
+    // Context: bound manually so that passes are claimed by one thread
+    "egui_context_Context_begin_pass",
+    "egui_context_Context_end_pass",
+
+    // Context: bound manually with an owned closure
+    "egui_context_Context_set_request_repaint_callback",
+
+    // Context: bound manually so that viewports can be run by EguiFfi
+    "egui_context_Context_show_viewport_deferred",
+    "egui_context_Context_show_viewport_immediate",
+
     // Popup: bound manually
     "egui_containers_popup_Popup_open_id",
 
     // Rect: bound manually
     "emath_rect_Rect_center",
     "emath_rect_Rect_set_center",
+
+    // Grid: bound manually so that row colors can be supplied from C#
+    "egui_grid_Grid_show",
+
+    // Menus: bound manually alongside the other menu containers
+    "egui_containers_menu_MenuBar_ui",
+    "egui_containers_menu_SubMenu_show",
 ];
 
 /// Types to exclude from generation.
@@ -109,6 +124,18 @@ const POINTER_TYPES: &[&str] = &[
     "Ui"
 ];
 
+/// Types which have an identical, blittable layout in C# and Rust. Functions that only take
+/// and return these types are invoked directly, without serializing their arguments.
+const POD_TYPES: &[&str] = &[
+    "Color32",
+    "Pos2",
+    "Rangef",
+    "Rect",
+    "Vec2",
+    "f32",
+    "u8"
+];
+
 /// Changes the names of autogenerated namespaces.
 const NAMESPACE_RENAMES: &[(&str, &str)] = &[
     ("Egui.Containers.ScrollArea", "Egui.Containers"),
@@ -137,14 +164,25 @@ const NAMESPACE_OVERRIDES: &[(&str, &str)] = &[
 /// Custom function names to define.
 const CUSTOM_FNS: &[&str] = &[
     "egui_EguiFfi_new",
+    "egui_EguiFfi_set_mesh_layout",
+    "egui_EguiFfi_set_output_format",
+    "egui_EguiFfi_set_paint_mode",
     "egui_context_Context_ref_decrement",
     "egui_context_Context_ref_count",
     "egui_context_Context_ref_id",
     "egui_context_Context_run_ffi",
+    "egui_context_Context_graphics_set_layer",
+    "egui_context_Context_input_patch",
+    "egui_context_Context_input_patch_for",
+    "egui_context_Context_options_patch",
+    "egui_context_Context_tessellation_options_patch",
     "egui_painter_Painter_add",
+    "egui_painter_Painter_add_ffi_callback",
     "egui_ui_Ui_set_enabled",
+    "egui_ui_Ui_style_patch",
     "egui_memory_Memory_options",
     "egui_memory_Memory_set_options",
+    "egui_memory_Memory_options_patch",
     "egui_containers_collapsing_header_EguiCollapsingStateShowToggleButtonParams_unpack"
 ];
 
@@ -412,9 +450,6 @@ const IGNORE_FNS: &[&str] = &[
 
     // Context: not yet implemented
     "egui_context_Context_set_immediate_viewport_renderer",
-    "egui_context_Context_set_request_repaint_callback",
-    "egui_context_Context_show_viewport_deferred",
-    "egui_context_Context_show_viewport_immediate",
     "egui_context_Context_loaders",
     "egui_context_Context_on_begin_pass",
     "egui_context_Context_on_end_pass",
@@ -889,7 +924,7 @@ const IGNORE_FNS: &[&str] = &[
     "egui_cache_frame_cache_FrameCache_new",
     "egui_cache_frame_cache_FrameCache_update",
 
-    // StyleModifier: not possible to bind to C# due to callbacks
+    // StyleModifier: bound manually through Popup::style
     "egui_style_StyleModifier_apply",
     "egui_style_StyleModifier_default",
     "egui_style_StyleModifier_new",
@@ -1088,6 +1123,11 @@ pub struct BindingsGenerator {
 impl BindingsGenerator {
     /// Creates autogenerated bindings for all `serde` types.
     pub fn generate(path: &std::path::Path) {
+        Self::new(path).run();
+    }
+
+    /// Loads the `egui` crate descriptions and prepares to write bindings to `path`.
+    fn new(path: &std::path::Path) -> Self {
         let mut krate = serde_json::from_str::<Crate>(include_str!("egui.json")).expect("Failed to parse egui");
         Self::merge_crates(&mut krate, &serde_json::from_str::<Crate>(include_str!("emath.json")).expect("Failed to parse emath"));
         Self::merge_crates(&mut krate, &serde_json::from_str::<Crate>(include_str!("epaint.json")).expect("Failed to parse epaint"));
@@ -1121,7 +1161,7 @@ impl BindingsGenerator {
             namespaces,
             name_to_id,
             public_fields
-        }.run()
+        }
     }
 
     /// Executes the bindings generator.
@@ -1217,20 +1257,21 @@ impl BindingsGenerator {
                     _ => None
                 };
 
-                if let Some(name) = inner_name
-                    && (HANDLE_TYPES.contains(&name) || POINTER_TYPES.contains(&name)) {
-                    BoundType {
+                match inner_name.filter(|name| HANDLE_TYPES.contains(name) || POINTER_TYPES.contains(name)) {
+                    Some(name) => BoundType {
                         kind: BoundTypeKind::Pointer { mutable: *is_mutable },
                         name: BoundTypeName::cs_rs(name, name)
-                    }
-                }
-                else {
-                    BoundType {
+                    },
+                    None => BoundType {
                         kind: BoundTypeKind::Reference { mutable: *is_mutable },
                         name: self.bound_ty_name(self_ty, type_)?
                     }
                 }
             },
+            Type::ImplTrait(_) if Self::ui_callback_output(ty).is_some() => BoundType {
+                kind: BoundTypeKind::Callback,
+                name: BoundTypeName::cs_rs("Action<Ui>", "EguiCallback")
+            },
             _ => BoundType {
                 kind: BoundTypeKind::Value,
                 name: self.bound_ty_name(self_ty, ty)?
@@ -1238,6 +1279,89 @@ impl BindingsGenerator {
         })
     }
 
+    /// If `ty` is an `impl FnOnce(&mut Ui) -> R` (or `FnMut`/`Fn`) closure, gets the closure's return type.
+    fn ui_callback_output(ty: &Type) -> Option<Option<&Type>> {
+        let Type::ImplTrait(bounds) = ty else { return None };
+        let mut traits = bounds.iter().filter_map(|x| match x {
+            GenericBound::TraitBound { trait_, .. } => Some(trait_),
+            _ => None
+        });
+        let (Some(RdPath { path, args, .. }), None) = (traits.next(), traits.next()) else { return None };
+        let Some(GenericArgs::Parenthesized { inputs, output }) = args.as_deref() else { return None };
+
+        let is_fn = matches!(path.split("::").last(), Some("FnOnce" | "FnMut" | "Fn"));
+        let takes_ui = match inputs.as_slice() {
+            [Type::BorrowedRef { is_mutable: true, type_, .. }] => matches!(&**type_, Type::ResolvedPath(inner) if inner.path.split("::").last() == Some("Ui")),
+            _ => false
+        };
+
+        (is_fn && takes_ui).then_some(output.as_ref())
+    }
+
+    /// Gets the order in which the parameters of `func` are passed between C# and Rust. This is the declared
+    /// order, except for functions that accept a closure over a [`Ui`]: like the hand-written container
+    /// bindings, those pass the [`Context`] or [`Ui`] first and move the receiver to just before the closure.
+    fn binding_param_order(&self, self_ty: Option<&str>, func: &Function) -> Vec<usize> {
+        let mut order = (0..func.sig.inputs.len()).collect::<Vec<_>>();
+        if self.callback_return(self_ty, func).is_ok() {
+            order.sort_by_key(|index| {
+                let (name, ty) = &func.sig.inputs[*index];
+                match self.bound_ty(self_ty, ty).map(|x| x.kind) {
+                    Some(BoundTypeKind::Pointer { .. }) => 0,
+                    Some(BoundTypeKind::Callback) => 3,
+                    _ if name == "self" => 2,
+                    _ => 1
+                }
+            });
+        }
+
+        order
+    }
+
+    /// If `ty` is a path with exactly one generic type argument, gets the last segment of the path and the argument.
+    fn single_generic_arg(ty: &Type) -> Option<(&str, &Type)> {
+        let Type::ResolvedPath(RdPath { path, args, .. }) = ty else { return None };
+        let Some(GenericArgs::AngleBracketed { args, .. }) = args.as_deref() else { return None };
+        let [GenericArg::Type(arg)] = args.as_slice() else { return None };
+        Some((path.split("::").last()?, arg))
+    }
+
+    /// Determines how a function which accepts a closure over a [`Ui`] can be bound automatically,
+    /// or explains why it cannot. Such functions must accept exactly one closure, which returns
+    /// a generic `R`, and either a [`Context`] or a [`Ui`] from which the closure's [`Ui`] context
+    /// is obtained in C#.
+    fn callback_return(&self, self_ty: Option<&str>, func: &Function) -> Result<CallbackReturn, &'static str> {
+        let mut outputs = func.sig.inputs.iter().filter_map(|(_, ty)| Self::ui_callback_output(ty));
+        let closure_output = match (outputs.next(), outputs.next()) {
+            (Some(output), None) => output,
+            (None, _) => return Err("no closure parameter"),
+            (Some(_), Some(_)) => return Err("multiple closure parameters")
+        };
+
+        let bound_tys = func.sig.inputs.iter().filter_map(|(_, ty)| self.bound_ty(self_ty, ty)).collect::<Vec<_>>();
+        if bound_tys.iter().any(|x| matches!(x.kind, BoundTypeKind::Reference { mutable: true })) {
+            return Err("closure alongside a `&mut` parameter");
+        }
+        else if !bound_tys.iter().any(|x| matches!(x.kind, BoundTypeKind::Pointer { .. }) && matches!(x.name.rs_name.as_str(), "Context" | "Ui")) {
+            return Err("closure without a `Context` or `Ui` parameter");
+        }
+
+        let Some(Type::Generic(closure_ty)) = closure_output else { return Err("closure does not return a generic type") };
+        let is_closure_ty = |ty: &Type| matches!(ty, Type::Generic(x) if x == closure_ty);
+        let inner_is = |ty: &Type, name: &str| matches!(Self::single_generic_arg(ty), Some((x, inner)) if x == name && is_closure_ty(inner));
+
+        match func.sig.output.as_ref() {
+            Some(output) if is_closure_ty(output) => Ok(CallbackReturn::Inner),
+            Some(output) => match Self::single_generic_arg(output) {
+                Some(("InnerResponse", inner)) if is_closure_ty(inner) => Ok(CallbackReturn::InnerResponse),
+                Some(("InnerResponse", inner)) if inner_is(inner, "Option") => Ok(CallbackReturn::InnerResponseOption),
+                Some(("Option", inner)) if inner_is(inner, "InnerResponse") => Ok(CallbackReturn::OptionInnerResponse),
+                _ => Err("closure result is not returned as `R`, `InnerResponse<R>` or `Option<InnerResponse<R>>`")
+            },
+            None => Err("closure result is not returned")
+        }
+    }
+
     /// Gets the C# and Rust names for a by-value type, or returns [`None`] if the type
     /// could not be resolved.
     fn bound_ty_name(&self, self_ty: Option<&str>, ty: &Type) -> Option<BoundTypeName> {
@@ -1289,25 +1413,20 @@ impl BindingsGenerator {
                 return None
             },
             Type::Generic(x) if x == "IdSource" => BoundTypeName::cs_rs("Id", "Id"),
-            Type::ImplTrait(x) => if x.len() == 1
-                && let Some(GenericBound::TraitBound { trait_: RdPath { path, args: trait_generics, .. }, .. }) = x.first() {
+            Type::ImplTrait(x) => match x.as_slice() {
+                [GenericBound::TraitBound { trait_: RdPath { path, args: trait_generics, .. }, .. }] => {
                     if path.contains("ToString") || path.contains("Display") {
                         BoundTypeName::cs_rs("string", "String")
                     }
-                    else if path == "Into"
-                        && let Some(GenericArgs::AngleBracketed { args, .. }) = trait_generics.as_deref()
-                        && args.len() == 1
-                        && let GenericArg::Type(inner_ty) = &args[0] {
-                        if let Type::ResolvedPath(RdPath { path, args, .. }) = inner_ty
-                            && path == "Cow"
-                            && format!("{args:?}").contains("Primitive(\"str\")") {
-                            BoundTypeName::cs_rs("string", "String")
-                        }
-                        else if format!("{inner_ty:?}").contains("StyleModifier") {
-                            BoundTypeName::cs_rs("Style", "Style")
-                        }
-                        else {
-                            self.bound_ty_name(self_ty, inner_ty)?
+                    else if path == "Into" {
+                        let Some(GenericArgs::AngleBracketed { args, .. }) = trait_generics.as_deref() else { return None };
+                        let [GenericArg::Type(inner_ty)] = args.as_slice() else { return None };
+
+                        match inner_ty {
+                            Type::ResolvedPath(RdPath { path, args, .. }) if path == "Cow"
+                                && format!("{args:?}").contains("Primitive(\"str\")") => BoundTypeName::cs_rs("string", "String"),
+                            _ if format!("{inner_ty:?}").contains("StyleModifier") => BoundTypeName::cs_rs("Style", "Style"),
+                            _ => self.bound_ty_name(self_ty, inner_ty)?
                         }
                     }
                     else if path.contains("Hash") || path.contains("IdSource") {
@@ -1319,10 +1438,9 @@ impl BindingsGenerator {
                     else {
                         return None
                     }
-                }
-                else {
-                    return None
-                }
+                },
+                _ => return None
+            },
             Type::Primitive(x) => BoundTypeName::cs_rs(Self::cs_primitive_name(&x)?, Self::rs_primitive_sized_name(&x)),
             Type::Tuple(items) => {
                 let inner_tys = items.iter().map(|x| self.bound_ty_name(self_ty, x)).collect::<Option<Vec<_>>>()?;
@@ -1377,28 +1495,154 @@ impl BindingsGenerator {
             result += &format!("using {old} = {}.{new};\n", self.namespaces[*old]);
         }
 
+        self.emit_pod_layouts(&mut result).expect("Failed to write plain-old-data layouts");
+
         let mut bound_ids = Vec::new();
+        let mut coverage = Vec::new();
         let binding_exclude_fns = BINDING_EXCLUDE_FNS.into_iter().collect::<HashSet<_>>();
 
         for id in self.gather_fns() {
             if binding_exclude_fns.contains(&&*self.fn_enum_variant_name(id)) {
+                coverage.push((id, FnCoverageStatus::Excluded, None));
                 continue;
             }
 
-            let result = self.emit_cs_fn_binding(&mut std::fmt::Formatter::new(&mut result, Default::default()), id);
+            let result = self.emit_cs_fn_binding(&mut result, id);
             
             if result.is_ok() {
                 bound_ids.push(id);
+                coverage.push((id, FnCoverageStatus::Autobound, None));
+            }
+            else {
+                coverage.push((id, FnCoverageStatus::Skipped, Some(self.skip_reason(id))));
             }
         }
         
         self.emit_fn_enum(&bound_ids);
+        self.emit_coverage_report(coverage);
         
         std::fs::write(self.output_path.join("EguiFn.g.cs"), result).expect("Failed to write C# function bindings");
     }
 
+    /// Writes a JSON report describing how each function was handled, grouped by module and type.
+    /// Functions in [`IGNORE_FNS`] and [`CUSTOM_FNS`] are added to `coverage`.
+    fn emit_coverage_report(&self, mut coverage: Vec<(RdId, FnCoverageStatus, Option<String>)>) {
+        let ignore_fns = IGNORE_FNS.into_iter().collect::<HashSet<_>>();
+        coverage.extend(self.gather_candidate_fns().into_iter()
+            .filter(|id| ignore_fns.contains(&&*self.fn_enum_variant_name(*id)))
+            .map(|id| (id, FnCoverageStatus::Ignored, None)));
+
+        let mut report = CoverageReport::default();
+        for (id, status, reason) in coverage {
+            let (path, ty) = match self.declaring_type(id) {
+                Some(impl_ty) => {
+                    let mut path = self.krate.paths[&impl_ty].path.clone();
+                    let ty = path.pop();
+                    (path, ty)
+                },
+                None => {
+                    let mut path = self.krate.paths[&id].path.clone();
+                    path.pop();
+                    (path, None)
+                }
+            };
+
+            let module = report.modules.entry(path.join("::")).or_default();
+            let fns = match ty {
+                Some(ty) => module.types.entry(ty).or_default(),
+                None => &mut module.functions
+            };
+
+            fns.push(FnCoverage {
+                id: self.fn_enum_variant_name(id),
+                name: self.krate.index[&id].name.clone().unwrap_or_default(),
+                status,
+                reason
+            });
+        }
+
+        report.modules.entry("egui_net".to_string()).or_default().functions.extend(CUSTOM_FNS.iter().map(|x| FnCoverage {
+            id: x.to_string(),
+            name: x.rsplit_once('_').map(|(_, name)| name).unwrap_or(x).to_string(),
+            status: FnCoverageStatus::Custom,
+            reason: None
+        }));
+
+        for module in report.modules.values_mut() {
+            module.functions.sort_by(|a, b| a.id.cmp(&b.id));
+            for fns in module.types.values_mut() {
+                fns.sort_by(|a, b| a.id.cmp(&b.id));
+            }
+        }
+
+        let json = serde_json::to_string_pretty(&report).expect("Failed to serialize coverage report");
+        std::fs::write(self.output_path.join("coverage.json"), json).expect("Failed to write coverage report");
+    }
+
+    /// Explains why [`Self::emit_cs_fn_binding`] could not bind the function with the given ID.
+    fn skip_reason(&self, id: RdId) -> String {
+        let item = &self.krate.index[&id];
+        let ItemEnum::Function(func) = &item.inner else { return "not a function".to_string() };
+
+        let impl_ty = self.declaring_type(id).and_then(|x| self.krate.index.get(&x));
+        if impl_ty.is_some_and(|x| !matches!(x.inner, ItemEnum::Struct(_) | ItemEnum::Enum(_))) {
+            return "declaring type is not a struct or enum".to_string();
+        }
+
+        let ty_name = impl_ty.and_then(|x| x.name.as_deref());
+        for (name, ty) in &func.sig.inputs {
+            if self.bound_ty(ty_name, ty).is_none() {
+                return format!("parameter `{name}`: {}", self.unsupported_ty_reason(ty_name, ty));
+            }
+        }
+
+        if func.sig.inputs.iter().any(|(_, ty)| Self::ui_callback_output(ty).is_some()) {
+            return match self.callback_return(ty_name, func) {
+                Ok(_) => "closure on a handle, pointer or enum type".to_string(),
+                Err(reason) => reason.to_string()
+            };
+        }
+
+        match func.sig.output.as_ref().map(|x| (x, self.bound_ty(ty_name, x))) {
+            Some((_, Some(BoundType { kind: BoundTypeKind::Pointer { .. }, .. }))) => "pointer return".to_string(),
+            Some((_, Some(BoundType { kind: BoundTypeKind::Reference { mutable: true }, .. }))) => "returns `&mut`".to_string(),
+            Some((output, None)) => format!("return type: {}", self.unsupported_ty_reason(ty_name, output)),
+            _ if impl_ty.is_some() && matches!(item.name.as_deref(), Some("new" | "default")) => "constructor of a handle, pointer or enum type".to_string(),
+            _ => "unsupported signature".to_string()
+        }
+    }
+
+    /// Describes why `ty` cannot be passed between C# and Rust.
+    fn unsupported_ty_reason(&self, self_ty: Option<&str>, ty: &Type) -> String {
+        match ty {
+            Type::Generic(_) => "generic parameter".to_string(),
+            Type::ImplTrait(_) => "`impl Trait` type".to_string(),
+            Type::DynTrait(_) => "trait object".to_string(),
+            Type::RawPointer { .. } => "raw pointer".to_string(),
+            Type::FunctionPointer(_) => "function pointer".to_string(),
+            Type::QualifiedPath { .. } => "associated type".to_string(),
+            Type::BorrowedRef { type_, .. } => match &**type_ {
+                Type::BorrowedRef { .. } => "nested reference".to_string(),
+                inner => self.unsupported_ty_reason(self_ty, inner)
+            },
+            Type::Tuple(items) => items.iter().find(|x| self.bound_ty_name(self_ty, x).is_none())
+                .map(|x| self.unsupported_ty_reason(self_ty, x))
+                .unwrap_or_else(|| "tuple".to_string()),
+            Type::Slice(inner) | Type::Array { type_: inner, .. } => self.unsupported_ty_reason(self_ty, inner),
+            Type::ResolvedPath(path) => match path.args.as_deref() {
+                Some(GenericArgs::AngleBracketed { args, .. }) if matches!(path.path.as_str(), "Arc" | "Option" | "Vec") => match args.as_slice() {
+                    [GenericArg::Type(inner)] => self.unsupported_ty_reason(self_ty, inner),
+                    _ => format!("`{}` with unsupported arguments", path.path)
+                },
+                _ => format!("type `{}` which is not serializable", path.path.split("::").last().unwrap_or_default())
+            },
+            Type::Primitive(x) => format!("primitive `{x}`"),
+            Type::Pat { .. } | Type::Infer => "pattern type".to_string()
+        }
+    }
+
     /// Writes a single C# method definition (with appropriate type qualifiers) to `f`.
-    fn emit_cs_fn_binding(&self, f: &mut std::fmt::Formatter, id: RdId) -> std::fmt::Result {
+    fn emit_cs_fn_binding(&self, f: &mut dyn std::fmt::Write, id: RdId) -> std::fmt::Result {
         if let Some(impl_ty) = self.declaring_type(id).and_then(|x| self.krate.index.get(&x)) {
             if matches!(impl_ty.inner, ItemEnum::Struct(_)) || matches!(impl_ty.inner, ItemEnum::Enum(_)) {
                 if let Some(ty_name) = impl_ty.name.as_deref() {
@@ -1408,13 +1652,13 @@ impl BindingsGenerator {
                     
                     if HANDLE_TYPES.contains(&ty_name) {
                         let mut fn_def = String::new();
-                        self.emit_cs_fn(&mut std::fmt::Formatter::new(&mut fn_def, Default::default()), Some(ty_name), id, DeclaringType::Handle)?;
+                        self.emit_cs_fn(&mut fn_def, Some(ty_name), id, DeclaringType::Handle)?;
                         
                         writeln!(f, "namespace {namespace} {{ public sealed partial class {display_name} {{\n{fn_def}\n}} }}")?;
                     }
                     else if POINTER_TYPES.contains(&ty_name) {
                         let mut fn_def = String::new();
-                        self.emit_cs_fn(&mut std::fmt::Formatter::new(&mut fn_def, Default::default()), Some(ty_name), id, DeclaringType::Pointer)?;
+                        self.emit_cs_fn(&mut fn_def, Some(ty_name), id, DeclaringType::Pointer)?;
                         
                         writeln!(f, "namespace {namespace} {{ public ref partial struct {display_name} {{\n{fn_def}\n}} }}")?;
                     }
@@ -1422,7 +1666,7 @@ impl BindingsGenerator {
                         let mut fn_def = String::new();
                         let primitive_enum = self.is_primitive_enum(impl_ty.id);
                         let decl_ty = if primitive_enum { DeclaringType::PrimitiveEnum } else { DeclaringType::Struct };
-                        self.emit_cs_fn(&mut std::fmt::Formatter::new(&mut fn_def, Default::default()), Some(ty_name), id, decl_ty)?;
+                        self.emit_cs_fn(&mut fn_def, Some(ty_name), id, decl_ty)?;
                         
                         if primitive_enum {
                             writeln!(f, "namespace {namespace} {{ public static partial class {display_name}Extensions {{\n{fn_def}\n}} }}")?;
@@ -1449,7 +1693,7 @@ impl BindingsGenerator {
             
             let mut fn_def = String::new();
 
-            self.emit_cs_fn(&mut std::fmt::Formatter::new(&mut fn_def, Default::default()), None, id, DeclaringType::None)?;
+            self.emit_cs_fn(&mut fn_def, None, id, DeclaringType::None)?;
             
             writeln!(f, "namespace {namespace} {{ public static partial class {short_namespace}Helpers {{\n{fn_def}\n}} }}")?;
             return Ok(());
@@ -1459,7 +1703,7 @@ impl BindingsGenerator {
     }
 
     /// Writes a single C# method definition to `f`.
-    fn emit_cs_fn(&self, f: &mut std::fmt::Formatter, ty_name: Option<&str>, id: RdId, decl_ty: DeclaringType) -> std::fmt::Result {
+    fn emit_cs_fn(&self, f: &mut dyn std::fmt::Write, ty_name: Option<&str>, id: RdId, decl_ty: DeclaringType) -> std::fmt::Result {
         let item = &self.krate.index[&id];
         let ItemEnum::Function(func) = &item.inner else { panic!("Expected id to refer to a function") };
 
@@ -1479,8 +1723,8 @@ impl BindingsGenerator {
             else if ty_name == Some(new_name.as_str()) {
                 format!("Get{new_name}")
             }
-            else if let Some(ContainerFormat::Enum(variants)) = self.registry.get(ty_name.unwrap_or_default())
-                && variants.values().find(|x| x.name == new_name).is_some() {
+            else if matches!(self.registry.get(ty_name.unwrap_or_default()),
+                Some(ContainerFormat::Enum(variants)) if variants.values().any(|x| x.name == new_name)) {
                 format!("From{new_name}")
             }
             else {
@@ -1489,6 +1733,11 @@ impl BindingsGenerator {
         };
 
         let constructor = !has_this && returns_this && (cs_name == "New" || cs_name == "Default");
+        let callback = func.sig.inputs.iter().any(|(_, ty)| Self::ui_callback_output(ty).is_some());
+
+        if callback && decl_ty != DeclaringType::Struct {
+            return Err(std::fmt::Error);
+        }
 
         if let Some(comment) = self.get_doc_comment(id) {
             writeln!(f, "/// {}", comment.replace("\n", "\n/// "))?;
@@ -1506,7 +1755,11 @@ impl BindingsGenerator {
             writeln!(f, "/// </summary>")?;
         }
 
-        if constructor {
+        if callback {
+            let fn_type = if has_this { FnType::Instance } else { FnType::Static };
+            self.emit_cs_callback_fn(f, ty_name, id, fn_type, &cs_name, func)?;
+        }
+        else if constructor {
             if decl_ty != DeclaringType::Struct {
                 return Err(std::fmt::Error);
             }
@@ -1538,7 +1791,7 @@ impl BindingsGenerator {
             };
 
             if !self.is_property(id, fn_type, returns_this, func) {
-                if let Some(name) = ty_name && HANDLE_TYPES.contains(&name) && name != "Context" {
+                if ty_name.is_some_and(|name| HANDLE_TYPES.contains(&name) && name != "Context") {
                     writeln!(f, "[MethodImpl(MethodImplOptions.AggressiveInlining | MethodImplOptions.Synchronized)]")?;
                 }
                 else {
@@ -1559,14 +1812,139 @@ impl BindingsGenerator {
         Ok(())
     }
 
+    /// Writes a pair of C# overloads for a function which accepts a closure over a [`Ui`]. The first
+    /// takes an `Action<Ui>`, and the second takes a `Func<Ui, R>` and also returns the closure's result.
+    fn emit_cs_callback_fn(&self, f: &mut dyn std::fmt::Write, ty_name: Option<&str>, id: RdId, fn_ty: FnType, cs_name: &str, func: &Function) -> std::fmt::Result {
+        let callback_return = self.callback_return(ty_name, func).map_err(|_| std::fmt::Error)?;
+
+        let mut params = Vec::new();
+        let mut passed = Vec::new();
+        let mut callback_name = None;
+        let mut ctx_name = None;
+        let mut ui_name = None;
+
+        for (index, (name, ty)) in func.sig.inputs.iter().enumerate() {
+            let bound_ty = self.bound_ty(ty_name, ty).ok_or(std::fmt::Error)?;
+            let param_name = Self::cs_param_name(fn_ty, name);
+
+            match bound_ty.kind {
+                BoundTypeKind::Pointer { .. } => {
+                    match bound_ty.name.rs_name.as_str() {
+                        "Context" => ctx_name = ctx_name.or(Some(param_name.clone())),
+                        "Ui" => ui_name = ui_name.or(Some(param_name.clone())),
+                        _ => {}
+                    }
+
+                    passed.push(("nuint".to_string(), format!("{param_name}.Ptr")));
+                },
+                BoundTypeKind::Callback => {
+                    passed.push(("EguiCallback".to_string(), format!("{param_name}Callback")));
+                    callback_name = Some(param_name.clone());
+                },
+                BoundTypeKind::Reference { .. } | BoundTypeKind::Value => {
+                    passed.push((bound_ty.name.cs_name.clone(), param_name.clone()));
+                }
+            }
+
+            if index > 0 || fn_ty != FnType::Instance {
+                params.push((bound_ty, param_name));
+            }
+        }
+
+        let callback_name = callback_name.ok_or(std::fmt::Error)?;
+        let (mut generics, mut args): (Vec<_>, Vec<_>) = self.binding_param_order(ty_name, func).into_iter()
+            .map(|index| passed[index].clone())
+            .unzip();
+        args.insert(0, format!("EguiFn.{}", self.fn_enum_variant_name(id)));
+        let (ctx_decl, ctx_name) = match (ctx_name, ui_name) {
+            (Some(ctx), _) => (String::new(), ctx),
+            (None, Some(ui)) => (format!("    var {ui}Ctx = {ui}.Ctx;\n"), format!("{ui}Ctx")),
+            (None, None) => return Err(std::fmt::Error)
+        };
+
+        match callback_return {
+            CallbackReturn::Inner => {},
+            CallbackReturn::InnerResponse | CallbackReturn::InnerResponseOption => generics.push("Response".to_string()),
+            CallbackReturn::OptionInnerResponse => generics.push("Response?".to_string())
+        }
+
+        let qualifiers = if fn_ty == FnType::Static { "static" } else { "" };
+        let pointer_checks = self.cs_binding_ptr_checks(ty_name, fn_ty, func);
+        let call = format!("EguiMarshal.Call<{}>({});", generics.join(", "), args.join(", "));
+        let inner_name = if callback_return == CallbackReturn::InnerResponseOption { "R?" } else { "R" };
+
+        for returns_inner in [false, true] {
+            let (delegate, type_params) = if returns_inner {
+                writeln!(f, "\n/// <inheritdoc cref=\"{cs_name}\"/>")?;
+                ("Func<Ui, R>", "<R>")
+            }
+            else {
+                ("Action<Ui>", "")
+            };
+
+            let (inner_response, inner_field) = if returns_inner {
+                (format!("InnerResponse<{inner_name}>"), "Inner = result, ")
+            }
+            else {
+                ("InnerResponse".to_string(), "")
+            };
+
+            let return_name = match callback_return {
+                CallbackReturn::Inner => if returns_inner { "R".to_string() } else { "void".to_string() },
+                CallbackReturn::InnerResponse | CallbackReturn::InnerResponseOption => inner_response.clone(),
+                CallbackReturn::OptionInnerResponse => format!("{inner_response}?")
+            };
+
+            let signature = params.iter()
+                .map(|(bound_ty, name)| match bound_ty.kind {
+                    BoundTypeKind::Callback => format!("{delegate} {name}"),
+                    _ => format!("{} {name}", bound_ty.name.cs_name)
+                })
+                .collect::<Vec<_>>().join(", ");
+
+            writeln!(f, "[MethodImpl(MethodImplOptions.AggressiveInlining)]")?;
+            writeln!(f, "public {qualifiers} {return_name} {cs_name}{type_params}({signature}) {{")?;
+            writeln!(f, "    {pointer_checks}")?;
+            write!(f, "{ctx_decl}")?;
+
+            if returns_inner {
+                writeln!(f, "    {inner_name} result = default!;")?;
+                writeln!(f, "    using var {callback_name}Callback = new EguiCallback(ptr => result = {callback_name}(new Ui({ctx_name}, ptr)));")?;
+            }
+            else {
+                writeln!(f, "    using var {callback_name}Callback = new EguiCallback(ptr => {callback_name}(new Ui({ctx_name}, ptr)));")?;
+            }
+
+            match callback_return {
+                CallbackReturn::Inner => {
+                    writeln!(f, "    {call}")?;
+                    if returns_inner {
+                        writeln!(f, "    return result;")?;
+                    }
+                },
+                CallbackReturn::InnerResponse | CallbackReturn::InnerResponseOption => {
+                    writeln!(f, "    var response = {call}")?;
+                    writeln!(f, "    return new {inner_response} {{ {inner_field}Response = response }};")?;
+                },
+                CallbackReturn::OptionInnerResponse => {
+                    writeln!(f, "    var response = {call}")?;
+                    writeln!(f, "    return response.HasValue ? new {inner_response} {{ {inner_field}Response = response.Value }} : null;")?;
+                }
+            }
+
+            writeln!(f, "}}")?;
+        }
+
+        Ok(())
+    }
+
     /// Whether this is the [`Ui`] interface function.
     fn is_ui_fn(item: &Item) -> bool {
-        if item.name.as_deref() == Some("ui")
-            && let ItemEnum::Function(func) = &item.inner {
-            func.sig.inputs.len() == 2 && func.sig.output.as_ref().map(|x| format!("{x:?}").contains("Response")).unwrap_or_default()
-        }
-        else {
-            false
+        match &item.inner {
+            ItemEnum::Function(func) if item.name.as_deref() == Some("ui") => {
+                func.sig.inputs.len() == 2 && func.sig.output.as_ref().map(|x| format!("{x:?}").contains("Response")).unwrap_or_default()
+            },
+            _ => false
         }
     }
 
@@ -1585,7 +1963,7 @@ impl BindingsGenerator {
     /// Emits the body of a C# function (excluding the name and return type).
     fn emit_cs_fn_def(
         &self,
-        f: &mut std::fmt::Formatter,
+        f: &mut dyn std::fmt::Write,
         ty_name: Option<&str>,
         id: RdId,
         fn_ty: FnType,
@@ -1602,7 +1980,7 @@ impl BindingsGenerator {
         }
 
         if property {
-            if let Some(name) = ty_name && HANDLE_TYPES.contains(&name) && name != "Context" {
+            if ty_name.is_some_and(|name| HANDLE_TYPES.contains(&name) && name != "Context") {
                 writeln!(f, "{{ [MethodImpl(MethodImplOptions.Synchronized)]\n get {{")?;
             }
             else {
@@ -1616,6 +1994,36 @@ impl BindingsGenerator {
         let pointer_checks = self.cs_binding_ptr_checks(ty_name, fn_ty, func);
         writeln!(f, "    {pointer_checks}")?;
 
+        if self.is_pod_fn(ty_name, func) {
+            let result_ty = sig.output.as_ref().and_then(|x| self.bound_ty(ty_name, x)).ok_or(std::fmt::Error)?.name.cs_name;
+            let args = self.cs_binding_arguments(ty_name, fn_ty, func).ok_or(std::fmt::Error)?.into_iter()
+                .chain(["&result".to_string()]).collect::<Vec<_>>().join(", ");
+
+            writeln!(f, "    {result_ty} result;")?;
+            writeln!(f, "    unsafe {{ EguiMarshal.AssertSuccess(EguiBindings.egui_pod_{}({args})); }}", self.fn_enum_variant_name(id))?;
+
+            if fn_ty == FnType::Constructor {
+                writeln!(f, "    this = result;")?;
+            }
+            else {
+                writeln!(f, "    return result;")?;
+            }
+        }
+        else {
+            self.emit_cs_fn_call(f, ty_name, id, fn_ty, func)?;
+        }
+
+        if property {
+            writeln!(f, "}}")?;
+        }
+
+        writeln!(f, "}}")?;
+        Ok(())
+    }
+
+    /// Emits a serialized call to a bound function, along with any assignments of its results.
+    fn emit_cs_fn_call(&self, f: &mut dyn std::fmt::Write, ty_name: Option<&str>, id: RdId, fn_ty: FnType, func: &Function) -> std::fmt::Result {
+        let sig = &func.sig;
         let binding_generics = self.cs_binding_generics(ty_name, func).ok_or(std::fmt::Error)?;
         if fn_ty == FnType::Constructor {
             write!(f, "    this = ")?;
@@ -1644,16 +2052,59 @@ impl BindingsGenerator {
             writeln!(f, "    return result;")?;
         }
 
-        if property {
+        Ok(())
+    }
+
+    /// Whether all parameters and the return value of `f` are [`POD_TYPES`] passed by value or
+    /// shared reference, so that it can be invoked without serialization.
+    fn is_pod_fn(&self, self_ty: Option<&str>, f: &Function) -> bool {
+        let is_pod = |ty: &Type, allow_reference: bool| self.bound_ty(self_ty, ty)
+            .map(|x| POD_TYPES.contains(&x.name.rs_name.as_str()) && match x.kind {
+                BoundTypeKind::Value => true,
+                BoundTypeKind::Reference { mutable: false } => allow_reference,
+                _ => false
+            })
+            .unwrap_or_default();
+
+        f.sig.output.as_ref().map(|x| is_pod(x, false)).unwrap_or_default()
+            && f.sig.inputs.iter().all(|(_, ty)| is_pod(ty, true))
+    }
+
+    /// Emits direct `extern "C"` exports for all bound functions that satisfy [`Self::is_pod_fn`].
+    fn emit_pod_fn_bindings(&self, f: &mut dyn std::fmt::Write, bound_ids: &[RdId]) -> std::fmt::Result {
+        for id in bound_ids {
+            let ty_name = self.declaring_type(*id).and_then(|x| self.krate.index[&x].name.clone());
+            let ItemEnum::Function(func) = &self.krate.index[id].inner else { panic!("Expected function items only") };
+
+            if !self.is_pod_fn(ty_name.as_deref(), func) {
+                continue;
+            }
+
+            let return_ty = func.sig.output.as_ref().and_then(|x| self.bound_ty(ty_name.as_deref(), x))
+                .expect("Failed to get return type").name.rs_name;
+            let params = func.sig.inputs.iter()
+                .map(|(name, ty)| format!("{name}_: {}", self.bound_ty(ty_name.as_deref(), ty).expect("Failed to get binding for type").name.rs_name))
+                .chain([format!("result: *mut {return_ty}")])
+                .collect::<Vec<_>>().join(", ");
+            let args = self.rs_binding_arguments(ty_name.as_deref(), func);
+            let arg_names = func.sig.inputs.iter().map(|(name, _)| format!("{name}_")).collect::<Vec<_>>();
+            let arg_tuple = if arg_names.len() == 1 { format!("({},)", arg_names[0]) } else { format!("({})", arg_names.join(", ")) };
+
+            let enum_name = self.fn_enum_variant_name(*id);
+            let path = self.fn_enum_path(*id);
+
+            writeln!(f, "/// Invokes [`EguiFn::{enum_name}`] without serialization.")?;
+            writeln!(f, "#[no_mangle]")?;
+            writeln!(f, "pub unsafe extern \"C\" fn egui_pod_{enum_name}({params}) -> EguiInvokeResult {{")?;
+            writeln!(f, "    invoke_pod(EguiFn::{enum_name}, {arg_tuple}, result, |{arg_tuple}| unsafe {{ {path}({args}) }})")?;
             writeln!(f, "}}")?;
         }
 
-        writeln!(f, "}}")?;
         Ok(())
     }
 
     /// Emits a registry of all functions that have been bound.
-    fn emit_fn_enum_bindings(&self, f: &mut std::fmt::Formatter, bound_ids: &[RdId]) -> std::fmt::Result {
+    fn emit_fn_enum_bindings(&self, f: &mut dyn std::fmt::Write, bound_ids: &[RdId]) -> std::fmt::Result {
         writeln!(f, "const AUTOGENERATED_EGUI_FNS: EguiFnMap = egui_fn_map()")?;
 
         for id in bound_ids {
@@ -1668,7 +2119,10 @@ impl BindingsGenerator {
             let enum_name = self.fn_enum_variant_name(*id);
             let path = self.fn_enum_path(*id);
 
-            let clone_return = if func.sig.output.as_ref()
+            let clone_return = if let Ok(callback_return) = self.callback_return(ty_name.as_deref(), func) {
+                callback_return.rs_suffix()
+            }
+            else if func.sig.output.as_ref()
                 .map(|x| matches!(self.bound_ty(ty_name.as_deref(), x).expect("Failed to get return type").kind, BoundTypeKind::Reference { mutable: false })).unwrap_or_default() {
                 ".to_owned()"
             }
@@ -1684,11 +2138,12 @@ impl BindingsGenerator {
 
     /// Gets the signature to use for an autobound Rust function.
     fn rs_binding_signature(&self, self_ty: Option<&str>, f: &Function) -> String {
-        f.sig.inputs.iter().map(|(name, ty)| {
+        self.binding_param_order(self_ty, f).into_iter().map(|index| {
+            let (name, ty) = &f.sig.inputs[index];
             let bound_ty = self.bound_ty(self_ty, ty).expect("Failed to get binding for type");
             let ty_name = match bound_ty.kind {
                 BoundTypeKind::Pointer { .. } => format!("EguiPointer<{}>", bound_ty.name.rs_name),
-                BoundTypeKind::Reference { .. } | BoundTypeKind::Value => bound_ty.name.rs_name.to_string(),
+                BoundTypeKind::Reference { .. } | BoundTypeKind::Value | BoundTypeKind::Callback => bound_ty.name.rs_name.to_string(),
             };
 
             format!("mut {name}_: {ty_name}")
@@ -1705,6 +2160,7 @@ impl BindingsGenerator {
                 BoundTypeKind::Reference { mutable: false } => format!("&{name}_"),
                 BoundTypeKind::Reference { mutable: true } => format!("&mut {name}_"),
                 BoundTypeKind::Value => format!("{name}_"),
+                BoundTypeKind::Callback => format!("|ui| {name}_.invoke_scoped(ui)"),
             }
         }).collect::<Vec<_>>().join(", ")
     }
@@ -1769,6 +2225,7 @@ impl BindingsGenerator {
             let bound_ty = self.bound_ty(self_ty, ty)?;
             let ty_name = match bound_ty.kind {
                 BoundTypeKind::Pointer { .. } => "nuint",
+                BoundTypeKind::Callback => "EguiCallback",
                 BoundTypeKind::Reference { .. } | BoundTypeKind::Value => bound_ty.name.cs_name.as_str(),
             };
 
@@ -1795,6 +2252,7 @@ impl BindingsGenerator {
 
             Some(match self.bound_ty(self_ty, ty)?.kind {
                 BoundTypeKind::Pointer { .. } => format!("{cs_name}.Ptr"),
+                BoundTypeKind::Callback => format!("{cs_name}Callback"),
                 BoundTypeKind::Reference { .. } | BoundTypeKind::Value => format!("{cs_name}"),
             })
         }).collect::<Option<Vec<_>>>()
@@ -1846,18 +2304,43 @@ impl BindingsGenerator {
         result += "    pub const ALL: &[Self] = &[\n    ";
         result += &variants.iter().map(|x| "Self::".to_string() + x).collect::<Vec<_>>().join(",\n    ").trim();
         result += "    ];\n";
+        result += "    /// The names of all enum variants, in discriminant order.\n";
+        result += "    pub const NAMES: &[&str] = &[\n    ";
+        result += &variants.iter().map(|x| format!("{x:?}")).collect::<Vec<_>>().join(",\n    ");
+        result += "    ];\n";
         result += "}\n";
 
         for (old, new) in TYPE_RENAMES {
             result += &format!("type {new} = {old};\n");
         }
 
-        self.emit_fn_enum_bindings(&mut std::fmt::Formatter::new(&mut result, Default::default()), bound_ids)
+        let schema = serde_json::to_string(&self.registry).expect("Failed to serialize serde registry");
+        result += "/// The version of `egui` for which bindings were generated.\n";
+        result += &format!("pub static EGUI_VERSION: &str = {:?};\n", self.krate.crate_version.as_deref().unwrap_or_default());
+        result += "/// A hash of all [`EguiFn`] variant names, in discriminant order.\n";
+        result += &format!("pub const EGUI_FN_NAMES_HASH: u64 = {};\n", Self::fnv1a_hash(variants.join("\n").as_bytes()));
+        result += "/// A hash of the reflected `serde` registry used to generate C# types.\n";
+        result += &format!("pub const EGUI_SCHEMA_HASH: u64 = {};\n", Self::fnv1a_hash(schema.as_bytes()));
+
+        self.emit_fn_enum_bindings(&mut result, bound_ids)
             .expect("Failed to emit function enum bindings");
+        self.emit_pod_fn_bindings(&mut result, bound_ids)
+            .expect("Failed to emit plain-old-data function bindings");
 
         std::fs::write(self.output_path.join("egui_fn.rs"), result).expect("Failed to write egui function enum");
     }
 
+    /// Computes a 64-bit FNV-1a hash of `bytes`. This hash is stable across
+    /// platforms and compiler versions.
+    fn fnv1a_hash(bytes: &[u8]) -> u64 {
+        let mut hash = 0xcbf29ce484222325u64;
+        for byte in bytes {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+
     /// Gets the variant names for an `enum` containing all public `egui` functions.
     fn fn_enum_variant_names(&self) -> Vec<String> {
         let mut result = self.gather_fns().into_iter().map(|id| self.fn_enum_variant_name(id)).collect::<Vec<_>>();
@@ -1921,7 +2404,13 @@ impl BindingsGenerator {
     /// Gets a list of all functions that should be bound for `egui`.
     fn gather_fns(&self) -> Vec<RdId> {
         let ignore_fns = IGNORE_FNS.into_iter().collect::<HashSet<_>>();
+        self.gather_candidate_fns().into_iter()
+            .filter(|id| !ignore_fns.contains(&&*self.fn_enum_variant_name(*id)))
+            .collect()
+    }
 
+    /// Gets the IDs of all public `egui` functions, including those in [`IGNORE_FNS`].
+    fn gather_candidate_fns(&self) -> Vec<RdId> {
         self.krate.index.iter()
             .filter_map(|(id, item)| (
                 item.crate_id == 0
@@ -1936,7 +2425,7 @@ impl BindingsGenerator {
                 && matches!(item.inner, ItemEnum::Function(_))
                 && {
                     let variant_name = self.fn_enum_variant_name(*id);
-                    variant_name.starts_with("e") && !ignore_fns.contains(&&*variant_name) && !variant_name.contains("__")
+                    variant_name.starts_with("e") && !variant_name.contains("__")
                     && !variant_name.rsplit_once("_").is_some_and(|(_, a)| a.chars().next() == a.chars().next().map(|x| x.to_ascii_uppercase()))
                 }
                 && item.name.as_deref().map(|x| !IGNORE_FN_NAMES.contains(&x)).unwrap_or(true)
@@ -2095,6 +2584,38 @@ impl BindingsGenerator {
         result
     }
 
+    /// Gives every struct in [`POD_TYPES`] an explicit sequential layout in C#, with the size and
+    /// alignment of its Rust counterpart, so that it may be passed to plain-old-data bindings directly.
+    fn emit_pod_layouts(&self, f: &mut dyn std::fmt::Write) -> std::fmt::Result {
+        for name in POD_TYPES.iter().filter(|x| Self::cs_primitive_name(x).is_none()) {
+            let namespace = self.namespaces.get(*name).cloned().unwrap_or_else(|| "Egui".to_string());
+            let display_name = Self::new_name(name).unwrap_or(name);
+            let (size, align) = Self::rs_pod_layout(name);
+            writeln!(f, "namespace {namespace} {{ [System.Runtime.InteropServices.StructLayout(System.Runtime.InteropServices.LayoutKind.Sequential, Size = {size}, Pack = {align})] public partial struct {display_name} {{ }} }}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the size and alignment of a type in [`POD_TYPES`].
+    fn rs_pod_layout(name: &str) -> (usize, usize) {
+        /// Gets the size and alignment of `T`.
+        fn layout<T>() -> (usize, usize) {
+            (size_of::<T>(), align_of::<T>())
+        }
+
+        match name {
+            "Color32" => layout::<Color32>(),
+            "Pos2" => layout::<Pos2>(),
+            "Rangef" => layout::<Rangef>(),
+            "Rect" => layout::<Rect>(),
+            "Vec2" => layout::<Vec2>(),
+            "f32" => layout::<f32>(),
+            "u8" => layout::<u8>(),
+            _ => panic!("No Rust layout is known for plain-old-data type {name}")
+        }
+    }
+
     /// Removes all code examples from the given documentation string.
     fn strip_code_comments(docs: &str) -> String {
         let mut result = Cow::Borrowed(docs);
@@ -2484,5 +3005,176 @@ enum BoundTypeKind {
         mutable: bool,
     },
     /// During function calls, the type should be deserialized and then passed by value.
-    Value
-}
\ No newline at end of file
+    Value,
+    /// The type is a closure over a [`Ui`], which is passed as a registered `EguiCallback` and
+    /// exposed to C# as a delegate.
+    Callback
+}
+
+/// How a function which accepts a [`BoundTypeKind::Callback`] returns the result `R` of its closure.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum CallbackReturn {
+    /// The function returns `R` directly.
+    Inner,
+    /// The function returns `InnerResponse<R>`.
+    InnerResponse,
+    /// The function returns `InnerResponse<Option<R>>`, where the closure might not have been run.
+    InnerResponseOption,
+    /// The function returns `Option<InnerResponse<R>>`.
+    OptionInnerResponse
+}
+
+impl CallbackReturn {
+    /// Gets the expression which converts the function's result into the value returned to C#.
+    fn rs_suffix(self) -> &'static str {
+        match self {
+            Self::Inner => "",
+            Self::InnerResponse | Self::InnerResponseOption => ".response",
+            Self::OptionInnerResponse => ".map(|x| x.response)"
+        }
+    }
+}
+
+/// Describes how every `egui` function was handled during generation.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct CoverageReport {
+    /// The functions of each module, keyed by module path.
+    pub modules: BTreeMap<String, ModuleCoverage>
+}
+
+impl CoverageReport {
+    /// Iterates over every function in the report.
+    pub fn fns_mut(&mut self) -> impl Iterator<Item = &mut FnCoverage> {
+        self.modules.values_mut().flat_map(|x| x.functions.iter_mut().chain(x.types.values_mut().flatten()))
+    }
+}
+
+/// The functions declared within a single module.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct ModuleCoverage {
+    /// The free functions of the module.
+    pub functions: Vec<FnCoverage>,
+    /// The methods of each type in the module, keyed by type name.
+    pub types: BTreeMap<String, Vec<FnCoverage>>
+}
+
+/// Describes how a single function was handled during generation.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct FnCoverage {
+    /// The name of the function's `EguiFn` variant.
+    pub id: String,
+    /// The name of the function.
+    pub name: String,
+    /// Whether and how the function was bound.
+    pub status: FnCoverageStatus,
+    /// If the function was skipped, then explains why.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>
+}
+
+/// Whether and how a function was bound.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FnCoverageStatus {
+    /// Bindings were generated automatically.
+    Autobound,
+    /// The function is bound by hand in `egui_net`. The generator cannot detect this, so it
+    /// is only reported once the hand-written bindings are known.
+    Manual,
+    /// The function is defined by `egui_net` rather than `egui`.
+    Custom,
+    /// The function appears in [`BINDING_EXCLUDE_FNS`].
+    Excluded,
+    /// The function appears in [`IGNORE_FNS`], so it has no `EguiFn` variant.
+    Ignored,
+    /// The generator could not bind the function.
+    Skipped
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensures that container functions which accept a closure over a [`Ui`] are bound automatically,
+    /// passing the [`Context`] or [`Ui`] first and the container just before its closure.
+    #[test]
+    fn binds_container_callbacks() {
+        const CONTAINER_FNS: &[(&str, &str)] = &[
+            ("egui_containers_panel_CentralPanel_show", "mut ctx_: EguiPointer<Context>, mut self_: CentralPanel, mut add_contents_: EguiCallback"),
+            ("egui_containers_panel_CentralPanel_show_inside", "mut ui_: EguiPointer<Ui>, mut self_: CentralPanel, mut add_contents_: EguiCallback"),
+            ("egui_containers_panel_SidePanel_show", "mut ctx_: EguiPointer<Context>, mut self_: SidePanel, mut add_contents_: EguiCallback"),
+            ("egui_containers_panel_SidePanel_show_inside", "mut ui_: EguiPointer<Ui>, mut self_: SidePanel, mut add_contents_: EguiCallback"),
+            ("egui_containers_panel_SidePanel_show_animated", "mut ctx_: EguiPointer<Context>, mut is_expanded_: bool, mut self_: SidePanel, mut add_contents_: EguiCallback"),
+            ("egui_containers_panel_SidePanel_show_animated_inside", "mut ui_: EguiPointer<Ui>, mut is_expanded_: bool, mut self_: SidePanel, mut add_contents_: EguiCallback")
+        ];
+
+        let generator = BindingsGenerator::new(&std::env::temp_dir());
+        let fns = generator.gather_fns().into_iter()
+            .map(|id| (generator.fn_enum_variant_name(id), id))
+            .collect::<HashMap<_, _>>();
+
+        for (name, signature) in CONTAINER_FNS {
+            assert!(!BINDING_EXCLUDE_FNS.contains(name), "{name} is excluded from generation");
+            let id = fns[*name];
+            let ty_name = generator.declaring_type(id).and_then(|x| generator.krate.index[&x].name.clone());
+            let ItemEnum::Function(func) = &generator.krate.index[&id].inner else { panic!("{name} is not a function") };
+
+            let closure = func.sig.inputs.iter()
+                .filter_map(|(_, ty)| generator.bound_ty(ty_name.as_deref(), ty))
+                .filter(|x| matches!(x.kind, BoundTypeKind::Callback))
+                .count();
+            assert_eq!(closure, 1, "{name} did not bind its closure as a callback");
+            assert!(generator.emit_cs_fn_binding(&mut String::new(), id).is_ok(), "{name} was not bound");
+            assert_eq!(generator.rs_binding_signature(ty_name.as_deref(), func), *signature);
+        }
+    }
+
+    /// Ensures that every struct in [`POD_TYPES`] is declared with the layout of its Rust counterpart,
+    /// and that the fields of generated structs are laid out the same way.
+    #[test]
+    fn lays_out_pod_types() {
+        let generator = BindingsGenerator::new(&std::env::temp_dir());
+        let mut layouts = String::new();
+        generator.emit_pod_layouts(&mut layouts).expect("Failed to write plain-old-data layouts");
+
+        for name in POD_TYPES.iter().filter(|x| BindingsGenerator::cs_primitive_name(x).is_none()) {
+            let (size, align) = BindingsGenerator::rs_pod_layout(name);
+            let display_name = BindingsGenerator::new_name(name).unwrap_or(name);
+            assert!(layouts.contains(&format!("Size = {size}, Pack = {align})] public partial struct {display_name} ")), "{name} was not given its Rust layout");
+
+            if !BINDING_EXCLUDE_TYPE_DEFINITIONS.contains(name) {
+                assert_eq!(cs_format_layout(&generator.registry, &Format::TypeName(name.to_string())), Some((size, align)), "{name} has different fields in C#");
+            }
+        }
+    }
+
+    /// Gets the size and alignment of the C# type generated for `format`, or [`None`] if it is not blittable.
+    fn cs_format_layout(registry: &Registry, format: &Format) -> Option<(usize, usize)> {
+        match format {
+            Format::U8 | Format::I8 => Some((1, 1)),
+            Format::U16 | Format::I16 => Some((2, 2)),
+            Format::U32 | Format::I32 | Format::F32 => Some((4, 4)),
+            Format::U64 | Format::I64 | Format::F64 => Some((8, 8)),
+            Format::TypeName(name) => match registry.get(name)? {
+                ContainerFormat::NewTypeStruct(format) => cs_format_layout(registry, format),
+                ContainerFormat::Struct(fields) => cs_sequential_layout(fields.iter()
+                    .map(|x| cs_format_layout(registry, &x.value))),
+                _ => None
+            },
+            _ => None
+        }
+    }
+
+    /// Gets the size and alignment of a C# struct with sequential layout, given those of its fields.
+    fn cs_sequential_layout(fields: impl IntoIterator<Item = Option<(usize, usize)>>) -> Option<(usize, usize)> {
+        let (mut size, mut align) = (0usize, 1);
+        for field in fields {
+            let (field_size, field_align) = field?;
+            size = size.next_multiple_of(field_align) + field_size;
+            align = align.max(field_align);
+        }
+
+        (size > 0).then(|| (size.next_multiple_of(align), align))
+    }
+}
@@ -1,8 +1,11 @@
 //! Allows for efficiently communicating with a C#-side `egui` Context from [Egui.NET](https://github.com/DouglasDwyer/Egui.NET).
 
 use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
 
 use egui::*;
+use egui::emath::Rot2;
 use egui::epaint::*;
 use serde::*;
 
@@ -11,15 +14,25 @@ use serde::*;
 pub struct EguiFfi {
     /// The serialized bytes of the most recent [`FullOutput`].
     full_output: FfiVec<u8>,
-    /// The meshes from the most recent call to [`Context::tessellate`].
-    meshes: FfiVec<(Rect, FfiTextureId, FfiVec<u32>, FfiVec<Vertex>)>,
+    /// The output of the root viewport.
+    frame: FfiFrame,
     /// The most recent raw input.
     raw_input: FfiVec<u8>,
+    /// The layout in which [`Self::set_primitives`] stores meshes.
+    mesh_layout: FfiMeshLayout,
+    /// Whether the output of each viewport is stored as meshes or shapes.
+    paint_mode: FfiPaintMode,
+    /// The conversions which [`Self::set_primitives`] applies to the meshes.
+    output_format: FfiOutputFormat,
+    /// The output of every viewport other than the root.
+    viewport_frames: FfiVec<(ViewportId, FfiFrame)>,
+    /// The serialized raw input of every viewport other than the root.
+    viewport_raw_inputs: FfiVec<u8>
 }
 
 impl EguiFfi {
-    /// Gets the most recent [`FullOutput`]. [`FullOutput::shapes`] and
-    /// [`FullOutput::viewport_output`] are not included.
+    /// Gets the most recent [`FullOutput`]. [`FullOutput::shapes`] is not included,
+    /// and the [`ViewportOutput::viewport_ui_cb`] of every viewport is `None`.
     pub fn full_output(&self) -> FullOutput {
         bincode::deserialize::<FullOutput2>(&self.full_output)
             .expect("Failed to deserialize FullOutput").into()
@@ -43,21 +56,162 @@ impl EguiFfi {
             .expect("Failed to serialize RawInput").into();
     }
 
-    /// Gets the most recent output of [`Context::tessellate`].
+    /// Gets the raw input of the viewports other than the root.
+    pub fn viewport_raw_inputs(&self) -> ViewportIdMap<RawInput> {
+        bincode::deserialize(&self.viewport_raw_inputs)
+            .expect("Failed to deserialize viewport RawInput")
+    }
+
+    /// Sets the raw input of the viewports other than the root. Immediate viewports
+    /// are run with their input whenever their parent runs, while a deferred viewport is
+    /// only run if it has input. The input is consumed by each run of the context.
+    pub fn set_viewport_raw_inputs(&mut self, raw_inputs: ViewportIdMap<RawInput>) {
+        self.viewport_raw_inputs = bincode::serialize(&raw_inputs)
+            .expect("Failed to serialize viewport RawInput").into();
+    }
+
+    /// Gets the most recent output of [`Context::tessellate`] for the root viewport.
+    /// See [`FfiFrame::meshes`].
     pub fn meshes(&self) -> Vec<(Rect, Mesh)> {
-        self.meshes.iter().map(|(clip_rect, texture_id, indices, vertices)|
-            (*clip_rect, Mesh {
-                indices: indices.to_vec(),
-                texture_id: (*texture_id).into(),
-                vertices: vertices.to_vec()
-            })).collect()
+        self.frame.meshes()
+    }
+
+    /// Gets the most recent output of [`Context::tessellate`] for the root viewport, when using
+    /// [`FfiOutputFormat::u16_indices`]. See [`FfiFrame::meshes_u16`].
+    pub fn meshes_u16(&self) -> Vec<(Rect, Mesh16)> {
+        self.frame.meshes_u16()
+    }
+
+    /// Sets the most recent output of [`Context::tessellate`] for the root viewport, stored according
+    /// to [`Self::mesh_layout`] and converted according to [`Self::output_format`]. The meshes should
+    /// have been tessellated at `pixels_per_point` for a screen that covers `screen_rect`.
+    pub fn set_meshes(&mut self, meshes: impl IntoIterator<Item = (Rect, Mesh)>, pixels_per_point: f32, screen_rect: Rect) {
+        self.set_primitives(meshes.into_iter().map(|(clip_rect, mesh)| ClippedPrimitive {
+            clip_rect,
+            primitive: Primitive::Mesh(mesh)
+        }), pixels_per_point, screen_rect);
+    }
+
+    /// Like [`Self::set_meshes`], but also stores every [`FfiPaintCallback`] in draw order
+    /// alongside the meshes. Other paint callbacks are ignored.
+    pub fn set_primitives(&mut self, primitives: impl IntoIterator<Item = ClippedPrimitive>, pixels_per_point: f32, screen_rect: Rect) {
+        self.frame = FfiFrame::new(primitives, self.mesh_layout, self.output_format, pixels_per_point, screen_rect);
+    }
+
+    /// Like [`Self::set_primitives`], but for several viewports at once. Each item holds the id of a
+    /// viewport, its primitives, and the `pixels_per_point` and `screen_rect` at which they were
+    /// tessellated. The output of every viewport other than the root is replaced, while the output
+    /// of the root viewport is only replaced if it is included.
+    pub fn set_viewport_primitives(&mut self, viewports: impl IntoIterator<Item = (ViewportId, Vec<ClippedPrimitive>, f32, Rect)>) {
+        let (mesh_layout, output_format) = (self.mesh_layout, self.output_format);
+        self.set_viewport_frames(viewports.into_iter().map(|(viewport_id, primitives, pixels_per_point, screen_rect)|
+            (viewport_id, FfiFrame::new(primitives, mesh_layout, output_format, pixels_per_point, screen_rect))));
+    }
+
+    /// Gets the shapes of the root viewport from the most recent call to [`Self::set_shapes`].
+    pub fn shapes(&self) -> Vec<FfiClippedShape> {
+        self.frame.shapes()
+    }
+
+    /// Sets the shapes of the root viewport, which were produced at `pixels_per_point`. This replaces
+    /// the meshes of the viewport, for hosts that draw shapes themselves. See [`FfiClippedShape`].
+    pub fn set_shapes(&mut self, shapes: impl IntoIterator<Item = ClippedShape>, pixels_per_point: f32) {
+        self.frame = FfiFrame::from_shapes(shapes, pixels_per_point);
+    }
+
+    /// Like [`Self::set_shapes`], but for several viewports at once. Each item holds the id of a
+    /// viewport, its shapes, and the `pixels_per_point` at which they were produced. The output of
+    /// every viewport other than the root is replaced, while the output of the root viewport is
+    /// only replaced if it is included.
+    pub fn set_viewport_shapes(&mut self, viewports: impl IntoIterator<Item = (ViewportId, Vec<ClippedShape>, f32)>) {
+        self.set_viewport_frames(viewports.into_iter().map(|(viewport_id, shapes, pixels_per_point)|
+            (viewport_id, FfiFrame::from_shapes(shapes, pixels_per_point))));
+    }
+
+    /// Replaces the output of every viewport other than the root, and the output of the root if it is included.
+    fn set_viewport_frames(&mut self, frames: impl IntoIterator<Item = (ViewportId, FfiFrame)>) {
+        let mut viewport_frames = Vec::new();
+        for (viewport_id, frame) in frames {
+            if viewport_id == ViewportId::ROOT {
+                self.frame = frame;
+            }
+            else {
+                viewport_frames.push((viewport_id, frame));
+            }
+        }
+
+        self.viewport_frames = viewport_frames.into();
     }
 
-    /// Sets the most recent output of [`Context::tessellate`].
-    pub fn set_meshes(&mut self, meshes: impl IntoIterator<Item = (Rect, Mesh)>) {
-        self.meshes = meshes.into_iter()
-            .map(|(clip_rect, mesh)| (clip_rect, mesh.texture_id.into(), mesh.indices.into(), mesh.vertices.into()))
-            .collect::<Vec<_>>().into()
+    /// Gets the output of a viewport, if it was included in the most recent output.
+    pub fn viewport_frame(&self, viewport_id: ViewportId) -> Option<&FfiFrame> {
+        self.viewport_frames().find(|(id, _)| *id == viewport_id).map(|(_, frame)| frame)
+    }
+
+    /// Gets the output of every viewport, beginning with the root.
+    pub fn viewport_frames(&self) -> impl Iterator<Item = (ViewportId, &FfiFrame)> {
+        std::iter::once((ViewportId::ROOT, &self.frame))
+            .chain(self.viewport_frames.iter().map(|(id, frame)| (*id, frame)))
+    }
+
+    /// Gets the paint callbacks of the root viewport from the most recent call to
+    /// [`Self::set_primitives`], in draw order.
+    pub fn paint_callbacks(&self) -> &[FfiPaintCallbackInfo] {
+        self.frame.paint_callbacks()
+    }
+
+    /// Gets the conversions which [`Self::set_primitives`] applies to the tessellated meshes.
+    pub fn output_format(&self) -> FfiOutputFormat {
+        self.output_format
+    }
+
+    /// Sets the conversions which [`Self::set_primitives`] applies to the tessellated meshes.
+    /// This takes effect on the next call.
+    pub fn set_output_format(&mut self, output_format: FfiOutputFormat) {
+        self.output_format = output_format;
+    }
+
+    /// Gets the layout in which [`Self::set_primitives`] stores meshes.
+    pub fn mesh_layout(&self) -> FfiMeshLayout {
+        self.mesh_layout
+    }
+
+    /// Sets the layout in which [`Self::set_primitives`] stores meshes. This takes effect on the next call.
+    pub fn set_mesh_layout(&mut self, mesh_layout: FfiMeshLayout) {
+        self.mesh_layout = mesh_layout;
+    }
+
+    /// Gets whether the output of each viewport should be stored as meshes or shapes.
+    pub fn paint_mode(&self) -> FfiPaintMode {
+        self.paint_mode
+    }
+
+    /// Sets whether the output of each viewport should be stored as meshes or shapes.
+    /// This takes effect on the next run of the context.
+    pub fn set_paint_mode(&mut self, paint_mode: FfiPaintMode) {
+        self.paint_mode = paint_mode;
+    }
+
+    /// Gets the vertices of all root viewport meshes, when using [`FfiMeshLayout::Contiguous`].
+    pub fn vertices(&self) -> &[Vertex] {
+        self.frame.vertices()
+    }
+
+    /// Gets the indices of all root viewport meshes, when using [`FfiMeshLayout::Contiguous`].
+    /// See [`FfiFrame::indices`].
+    pub fn indices(&self) -> &[u32] {
+        self.frame.indices()
+    }
+
+    /// Gets the 16-bit indices of all root viewport meshes, when using [`FfiMeshLayout::Contiguous`]
+    /// and [`FfiOutputFormat::u16_indices`]. See [`FfiFrame::indices_u16`].
+    pub fn indices_u16(&self) -> &[u16] {
+        self.frame.indices_u16()
+    }
+
+    /// Gets the draw commands of the root viewport, when using [`FfiMeshLayout::Contiguous`].
+    pub fn draw_commands(&self) -> &[FfiDrawCommand] {
+        self.frame.draw_commands()
     }
 }
 
@@ -65,17 +219,544 @@ impl Default for EguiFfi {
     fn default() -> Self {
         let mut result = Self {
             full_output: Vec::new().into(),
-            meshes: Vec::new().into(),
-            raw_input: Vec::new().into()
+            frame: FfiFrame::default(),
+            raw_input: Vec::new().into(),
+            mesh_layout: FfiMeshLayout::default(),
+            paint_mode: FfiPaintMode::default(),
+            output_format: FfiOutputFormat::default(),
+            viewport_frames: Vec::new().into(),
+            viewport_raw_inputs: Vec::new().into()
         };
 
         result.set_full_output(FullOutput::default());
         result.set_raw_input(RawInput::default());
+        result.set_viewport_raw_inputs(ViewportIdMap::default());
         
         result
     }
 }
 
+/// The output of a single viewport, as either meshes or shapes.
+#[repr(C)]
+pub struct FfiFrame {
+    /// The meshes, when using [`FfiMeshLayout::Separate`].
+    meshes: FfiVec<(Rect, FfiTextureId, FfiVec<u32>, FfiVec<Vertex>)>,
+    /// The meshes, when using [`FfiMeshLayout::Separate`] and [`FfiOutputFormat::u16_indices`].
+    meshes_u16: FfiVec<(Rect, FfiTextureId, FfiVec<u16>, FfiVec<Vertex>)>,
+    /// The vertices of all meshes, when using [`FfiMeshLayout::Contiguous`].
+    vertices: FfiVec<Vertex>,
+    /// The indices of all meshes, when using [`FfiMeshLayout::Contiguous`].
+    indices: FfiVec<u32>,
+    /// The indices of all meshes, when using [`FfiMeshLayout::Contiguous`] and [`FfiOutputFormat::u16_indices`].
+    indices_u16: FfiVec<u16>,
+    /// The draw commands which refer to [`Self::vertices`] and [`Self::indices`],
+    /// when using [`FfiMeshLayout::Contiguous`].
+    draw_commands: FfiVec<FfiDrawCommand>,
+    /// The host-identified paint callbacks, in draw order.
+    paint_callbacks: FfiVec<FfiPaintCallbackInfo>,
+    /// The serialized shapes, when using [`FfiPaintMode::Shapes`].
+    shapes: FfiVec<u8>
+}
+
+impl FfiFrame {
+    /// Stores `primitives` in the given layout and format. The primitives should have been
+    /// tessellated at `pixels_per_point` for a screen that covers `screen_rect`.
+    fn new(primitives: impl IntoIterator<Item = ClippedPrimitive>, mesh_layout: FfiMeshLayout, format: FfiOutputFormat, pixels_per_point: f32, screen_rect: Rect) -> Self {
+        let mut paint_callbacks = Vec::<FfiPaintCallbackInfo>::new();
+        let mut meshes = Vec::<(Rect, FfiTextureId, FfiVec<u32>, FfiVec<Vertex>)>::new();
+        let mut meshes_u16 = Vec::<(Rect, FfiTextureId, FfiVec<u16>, FfiVec<Vertex>)>::new();
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let mut draw_commands = Vec::<FfiDrawCommand>::new();
+        let max_vertices = if format.u16_indices { u16::MAX as usize + 1 } else { u32::MAX as usize };
+
+        for ClippedPrimitive { clip_rect, primitive } in primitives {
+            let clip_rect = format.convert_clip_rect(clip_rect, pixels_per_point, screen_rect);
+
+            let mut mesh = match primitive {
+                Primitive::Mesh(mesh) => mesh,
+                Primitive::Callback(callback) => {
+                    if let Some(FfiPaintCallback { id }) = callback.callback.downcast_ref::<FfiPaintCallback>() {
+                        paint_callbacks.push(FfiPaintCallbackInfo {
+                            id: *id,
+                            rect: format.convert_rect(callback.rect, pixels_per_point),
+                            clip_rect,
+                            draw_index: match mesh_layout {
+                                FfiMeshLayout::Separate if format.u16_indices => meshes_u16.len(),
+                                FfiMeshLayout::Separate => meshes.len(),
+                                FfiMeshLayout::Contiguous => draw_commands.len()
+                            } as u32
+                        });
+                    }
+
+                    continue;
+                }
+            };
+
+            for vertex in &mut mesh.vertices {
+                *vertex = format.convert_vertex(*vertex, pixels_per_point);
+            }
+
+            if mesh_layout == FfiMeshLayout::Separate {
+                if format.u16_indices {
+                    meshes_u16.extend(mesh.split_to_u16().into_iter()
+                        .map(|x| (clip_rect, x.texture_id.into(), x.indices.into(), x.vertices.into())));
+                }
+                else {
+                    meshes.push((clip_rect, mesh.texture_id.into(), mesh.indices.into(), mesh.vertices.into()));
+                }
+
+                continue;
+            }
+
+            let parts = if mesh.vertices.len() > max_vertices {
+                mesh.split_to_u16().into_iter()
+                    .map(|x| Mesh {
+                        indices: x.indices.into_iter().map(u32::from).collect(),
+                        vertices: x.vertices,
+                        texture_id: x.texture_id
+                    })
+                    .collect()
+            }
+            else {
+                vec![mesh]
+            };
+
+            for mesh in parts {
+                if mesh.is_empty() {
+                    continue;
+                }
+
+                let texture_id = FfiTextureId::from(mesh.texture_id);
+                let after_callback = paint_callbacks.last().is_some_and(|x| x.draw_index as usize == draw_commands.len());
+                if after_callback || !draw_commands.last().is_some_and(|last| last.texture_id == texture_id
+                    && last.clip_rect == clip_rect
+                    && vertices.len() - last.vertex_offset as usize + mesh.vertices.len() <= max_vertices) {
+                    draw_commands.push(FfiDrawCommand {
+                        clip_rect,
+                        texture_id,
+                        index_offset: indices.len() as u32,
+                        index_count: 0,
+                        vertex_offset: vertices.len() as u32
+                    });
+                }
+
+                let command = draw_commands.last_mut().expect("Failed to get draw command");
+
+                let base_vertex = vertices.len() as u32 - command.vertex_offset;
+                indices.extend(mesh.indices.iter().map(|x| x + base_vertex));
+                vertices.extend(mesh.vertices);
+                command.index_count += mesh.indices.len() as u32;
+            }
+        }
+
+        let (indices, indices_u16) = if format.u16_indices {
+            (Vec::new(), indices.into_iter().map(|x| x as u16).collect())
+        }
+        else {
+            (indices, Vec::new())
+        };
+
+        Self {
+            meshes: meshes.into(),
+            meshes_u16: meshes_u16.into(),
+            vertices: vertices.into(),
+            indices: indices.into(),
+            indices_u16: indices_u16.into(),
+            draw_commands: draw_commands.into(),
+            paint_callbacks: paint_callbacks.into(),
+            shapes: Vec::new().into()
+        }
+    }
+
+    /// Stores `shapes`, which were produced at `pixels_per_point`.
+    fn from_shapes(shapes: impl IntoIterator<Item = ClippedShape>, pixels_per_point: f32) -> Self {
+        let mut tessellator = Tessellator::new(pixels_per_point, TessellationOptions::default(), [1, 1], Vec::new());
+        let mut result = Vec::new();
+        for ClippedShape { clip_rect, shape } in shapes {
+            FfiClippedShape::convert(clip_rect, shape, &mut tessellator, &mut result);
+        }
+
+        Self {
+            shapes: bincode::serialize(&result).expect("Failed to serialize shapes").into(),
+            ..Self::default()
+        }
+    }
+
+    /// Gets the meshes, when using [`FfiMeshLayout::Separate`]. This is empty
+    /// if [`FfiOutputFormat::u16_indices`] is set.
+    pub fn meshes(&self) -> Vec<(Rect, Mesh)> {
+        self.meshes.iter().map(|(clip_rect, texture_id, indices, vertices)|
+            (*clip_rect, Mesh {
+                indices: indices.to_vec(),
+                texture_id: (*texture_id).into(),
+                vertices: vertices.to_vec()
+            })).collect()
+    }
+
+    /// Gets the meshes with 16-bit indices, when using [`FfiMeshLayout::Separate`] and
+    /// [`FfiOutputFormat::u16_indices`]. Meshes with more than 65536 vertices are divided.
+    pub fn meshes_u16(&self) -> Vec<(Rect, Mesh16)> {
+        self.meshes_u16.iter().map(|(clip_rect, texture_id, indices, vertices)|
+            (*clip_rect, Mesh16 {
+                indices: indices.to_vec(),
+                texture_id: (*texture_id).into(),
+                vertices: vertices.to_vec()
+            })).collect()
+    }
+
+    /// Gets the vertices of all meshes, when using [`FfiMeshLayout::Contiguous`].
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    /// Gets the indices of all meshes, when using [`FfiMeshLayout::Contiguous`]. Each index is relative
+    /// to the [`FfiDrawCommand::vertex_offset`] of the command that draws it. This is empty
+    /// if [`FfiOutputFormat::u16_indices`] is set.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Gets the 16-bit indices of all meshes, when using [`FfiMeshLayout::Contiguous`] and
+    /// [`FfiOutputFormat::u16_indices`]. Each index is relative to the
+    /// [`FfiDrawCommand::vertex_offset`] of the command that draws it.
+    pub fn indices_u16(&self) -> &[u16] {
+        &self.indices_u16
+    }
+
+    /// Gets the draw commands, when using [`FfiMeshLayout::Contiguous`].
+    pub fn draw_commands(&self) -> &[FfiDrawCommand] {
+        &self.draw_commands
+    }
+
+    /// Gets the host-identified paint callbacks, in draw order.
+    pub fn paint_callbacks(&self) -> &[FfiPaintCallbackInfo] {
+        &self.paint_callbacks
+    }
+
+    /// Gets the shapes, when using [`FfiPaintMode::Shapes`].
+    pub fn shapes(&self) -> Vec<FfiClippedShape> {
+        if self.shapes.is_empty() {
+            Vec::new()
+        }
+        else {
+            bincode::deserialize(&self.shapes).expect("Failed to deserialize shapes")
+        }
+    }
+}
+
+impl Default for FfiFrame {
+    fn default() -> Self {
+        Self {
+            meshes: Vec::new().into(),
+            meshes_u16: Vec::new().into(),
+            vertices: Vec::new().into(),
+            indices: Vec::new().into(),
+            indices_u16: Vec::new().into(),
+            draw_commands: Vec::new().into(),
+            paint_callbacks: Vec::new().into(),
+            shapes: Vec::new().into()
+        }
+    }
+}
+
+/// Determines how [`EguiFfi::set_primitives`] stores the tessellated meshes.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
+pub enum FfiMeshLayout {
+    /// Each mesh has its own vertex and index buffers, available from [`EguiFfi::meshes`]
+    /// or [`EguiFfi::meshes_u16`].
+    #[default]
+    Separate,
+    /// All meshes share one vertex buffer and one index buffer, and are drawn by the
+    /// commands in [`EguiFfi::draw_commands`]. Adjacent meshes with the same texture
+    /// and clip rectangle are merged into a single command.
+    Contiguous
+}
+
+/// Determines whether the output of each viewport is tessellated into meshes, or stored as shapes
+/// for hosts with their own vector renderers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
+pub enum FfiPaintMode {
+    /// The shapes are tessellated, and the meshes stored according to [`FfiMeshLayout`].
+    #[default]
+    Meshes,
+    /// The shapes are stored without tessellation, and are available from [`EguiFfi::shapes`].
+    Shapes
+}
+
+/// Conversions which [`EguiFfi::set_primitives`] applies to tessellated meshes, so that
+/// hosts can upload them without processing each vertex.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct FfiOutputFormat {
+    /// Whether vertex positions and clip rectangles are given in physical pixels rather than points.
+    pub physical_pixels: bool,
+    /// Whether vertex colors are converted from gamma space to linear space.
+    pub linear_colors: bool,
+    /// Whether indices are stored as 16-bit values, in [`EguiFfi::meshes_u16`] or
+    /// [`EguiFfi::indices_u16`] depending on the [`FfiMeshLayout`]. Large meshes are
+    /// divided, and draw commands split, so that every mesh or command addresses at
+    /// most 65536 vertices.
+    pub u16_indices: bool,
+    /// Whether clip rectangles are converted to physical pixels, rounded to whole pixels,
+    /// and clamped to the screen, so that they may be used directly as scissor rectangles.
+    pub integer_scissor_rects: bool
+}
+
+impl FfiOutputFormat {
+    /// Converts a tessellated vertex.
+    fn convert_vertex(&self, mut vertex: Vertex, pixels_per_point: f32) -> Vertex {
+        if self.physical_pixels {
+            vertex.pos = (vertex.pos.to_vec2() * pixels_per_point).to_pos2();
+        }
+
+        if self.linear_colors {
+            let [r, g, b, a] = vertex.color.to_array();
+            let linear = |x| ecolor::linear_u8_from_linear_f32(ecolor::linear_f32_from_gamma_u8(x));
+            vertex.color = Color32::from_rgba_premultiplied(linear(r), linear(g), linear(b), a);
+        }
+
+        vertex
+    }
+
+    /// Converts the target rectangle of a paint callback.
+    fn convert_rect(&self, rect: Rect, pixels_per_point: f32) -> Rect {
+        if self.physical_pixels {
+            Rect::from_min_max((rect.min.to_vec2() * pixels_per_point).to_pos2(), (rect.max.to_vec2() * pixels_per_point).to_pos2())
+        }
+        else {
+            rect
+        }
+    }
+
+    /// Converts the clip rectangle of a mesh.
+    fn convert_clip_rect(&self, clip_rect: Rect, pixels_per_point: f32, screen_rect: Rect) -> Rect {
+        if self.integer_scissor_rects {
+            let screen_max = (screen_rect.max.to_vec2() * pixels_per_point).round().max(Vec2::ZERO).to_pos2();
+            let min = (clip_rect.min.to_vec2() * pixels_per_point).round().to_pos2().clamp(Pos2::ZERO, screen_max);
+            let max = (clip_rect.max.to_vec2() * pixels_per_point).round().to_pos2().clamp(min, screen_max);
+            Rect::from_min_max(min, max)
+        }
+        else {
+            self.convert_rect(clip_rect, pixels_per_point)
+        }
+    }
+}
+
+/// Draws a range of the shared index buffer of [`EguiFfi`].
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct FfiDrawCommand {
+    /// The clip rectangle, converted according to [`FfiOutputFormat`].
+    pub clip_rect: Rect,
+    /// The texture to sample.
+    texture_id: FfiTextureId,
+    /// The first index to draw.
+    pub index_offset: u32,
+    /// The number of indices to draw.
+    pub index_count: u32,
+    /// The vertex to which each index is relative.
+    pub vertex_offset: u32
+}
+
+impl FfiDrawCommand {
+    /// Gets the texture to sample.
+    pub fn texture_id(&self) -> TextureId {
+        self.texture_id.into()
+    }
+}
+
+/// The payload of a [`PaintCallback`] that should be drawn by the host. Paint callbacks
+/// carrying any other payload are not passed to the host.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FfiPaintCallback {
+    /// The host-defined identifier of the callback.
+    pub id: u64
+}
+
+/// A paint callback that the host should draw, along with where it appears among the meshes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(C)]
+pub struct FfiPaintCallbackInfo {
+    /// The host-defined identifier of the callback.
+    pub id: u64,
+    /// The area in which the callback should draw, converted according to [`FfiOutputFormat::physical_pixels`].
+    pub rect: Rect,
+    /// The clip rectangle, converted according to [`FfiOutputFormat`].
+    pub clip_rect: Rect,
+    /// The number of meshes, or draw commands when using [`FfiMeshLayout::Contiguous`],
+    /// which are drawn before this callback.
+    pub draw_index: u32
+}
+
+/// A [`ClippedShape`] in a serializable form, for hosts that draw shapes themselves.
+/// Nested shapes are flattened, text is reduced to the placement of each glyph, and
+/// paths with callback-computed colors are tessellated into meshes. Paint callbacks
+/// carrying anything other than an [`FfiPaintCallback`] are omitted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FfiClippedShape {
+    /// The clip rectangle.
+    pub clip_rect: Rect,
+    /// The shape to draw.
+    pub shape: FfiShape
+}
+
+impl FfiClippedShape {
+    /// Converts `shape` and appends the result to `output`.
+    fn convert(clip_rect: Rect, shape: Shape, tessellator: &mut Tessellator, output: &mut Vec<Self>) {
+        let shape = match shape {
+            Shape::Noop => return,
+            Shape::Vec(shapes) => {
+                for shape in shapes {
+                    Self::convert(clip_rect, shape, tessellator, output);
+                }
+                return;
+            },
+            Shape::Circle(x) => FfiShape::Circle(x),
+            Shape::Ellipse(x) => FfiShape::Ellipse(x),
+            Shape::LineSegment { points, stroke } => FfiShape::LineSegment { points, stroke },
+            Shape::Rect(x) => FfiShape::Rect(x),
+            Shape::Text(x) if x.opacity_factor <= 0.0 => return,
+            Shape::Text(x) => FfiShape::Text(x.into()),
+            Shape::Mesh(x) => FfiShape::Mesh(Arc::unwrap_or_clone(x)),
+            Shape::Path(PathShape { stroke: PathStroke { color: ColorMode::UV(_), .. }, .. })
+                | Shape::QuadraticBezier(QuadraticBezierShape { stroke: PathStroke { color: ColorMode::UV(_), .. }, .. })
+                | Shape::CubicBezier(CubicBezierShape { stroke: PathStroke { color: ColorMode::UV(_), .. }, .. }) => {
+                let mut mesh = Mesh::default();
+                tessellator.tessellate_shape(shape, &mut mesh);
+                FfiShape::Mesh(mesh)
+            },
+            Shape::Path(x) => FfiShape::Path(x),
+            Shape::QuadraticBezier(x) => FfiShape::QuadraticBezier(x),
+            Shape::CubicBezier(x) => FfiShape::CubicBezier(x),
+            Shape::Callback(x) => match x.callback.downcast_ref::<FfiPaintCallback>() {
+                Some(FfiPaintCallback { id }) => FfiShape::Callback { id: *id, rect: x.rect },
+                None => return
+            }
+        };
+
+        output.push(Self { clip_rect, shape });
+    }
+}
+
+/// A serializable version of [`Shape`]. All coordinates are in points.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FfiShape {
+    /// [`Shape::Circle`]
+    Circle(CircleShape),
+    /// [`Shape::Ellipse`]
+    Ellipse(EllipseShape),
+    /// [`Shape::LineSegment`]
+    LineSegment {
+        /// The ends of the line.
+        points: [Pos2; 2],
+        /// The stroke of the line.
+        stroke: Stroke
+    },
+    /// [`Shape::Path`]
+    Path(PathShape),
+    /// [`Shape::Rect`]
+    Rect(RectShape),
+    /// [`Shape::Text`]
+    Text(FfiTextShape),
+    /// [`Shape::Mesh`]
+    Mesh(Mesh),
+    /// [`Shape::QuadraticBezier`]
+    QuadraticBezier(QuadraticBezierShape),
+    /// [`Shape::CubicBezier`]
+    CubicBezier(CubicBezierShape),
+    /// A [`Shape::Callback`] carrying an [`FfiPaintCallback`].
+    Callback {
+        /// The host-defined identifier of the callback.
+        id: u64,
+        /// The area in which the callback should draw.
+        rect: Rect
+    }
+}
+
+/// A [`TextShape`], reduced to the placement of each visible glyph. Backgrounds, underlines
+/// and strikethroughs from the text formats are not included.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FfiTextShape {
+    /// The top left corner of the text.
+    pub pos: Pos2,
+    /// The rotation of the text about [`Self::pos`], in radians.
+    pub angle: f32,
+    /// The stroke with which to underline the whole text.
+    pub underline: Stroke,
+    /// The glyphs, in layout order.
+    pub glyphs: Vec<FfiGlyph>
+}
+
+impl From<TextShape> for FfiTextShape {
+    fn from(value: TextShape) -> Self {
+        let rotation = Rot2::from_angle(value.angle);
+        let mut glyphs = Vec::new();
+
+        for row in &value.galley.rows {
+            let mut vertex = row.row.visuals.glyph_vertex_range.start;
+            for glyph in row.row.glyphs.iter().filter(|x| !x.uv_rect.is_nothing()) {
+                let mut color = row.row.visuals.mesh.vertices.get(vertex).map_or(Color32::PLACEHOLDER, |x| x.color);
+                vertex += 4;
+
+                if let Some(override_text_color) = value.override_text_color {
+                    color = override_text_color;
+                }
+                else if color == Color32::PLACEHOLDER {
+                    color = value.fallback_color;
+                }
+
+                if value.opacity_factor < 1.0 {
+                    color = color.gamma_multiply(value.opacity_factor);
+                }
+
+                glyphs.push(FfiGlyph {
+                    chr: glyph.chr,
+                    pos: value.pos + rotation * (row.pos.to_vec2() + glyph.pos.to_vec2()),
+                    advance_width: glyph.advance_width,
+                    font_height: glyph.font_height,
+                    font_ascent: glyph.font_ascent,
+                    image_rect: Rect::from_min_size(glyph.uv_rect.offset.to_pos2(), glyph.uv_rect.size),
+                    uv_rect: Rect::from_min_max(
+                        pos2(glyph.uv_rect.min[0] as f32, glyph.uv_rect.min[1] as f32),
+                        pos2(glyph.uv_rect.max[0] as f32, glyph.uv_rect.max[1] as f32)),
+                    color
+                });
+            }
+        }
+
+        Self {
+            pos: value.pos,
+            angle: value.angle,
+            underline: value.underline,
+            glyphs
+        }
+    }
+}
+
+/// The placement of a single glyph of an [`FfiTextShape`].
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FfiGlyph {
+    /// The character that the glyph represents.
+    pub chr: char,
+    /// The left end of the glyph's baseline, with the rotation of the text applied.
+    pub pos: Pos2,
+    /// The logical width of the glyph.
+    pub advance_width: f32,
+    /// The row height of the glyph's font.
+    pub font_height: f32,
+    /// The ascent of the glyph's font.
+    pub font_ascent: f32,
+    /// The area covered by the glyph's image, relative to [`Self::pos`] and before rotation.
+    pub image_rect: Rect,
+    /// The area of the font texture that holds the glyph's image, in texels.
+    pub uv_rect: Rect,
+    /// The color of the glyph, with the overrides and opacity of the text applied.
+    pub color: Color32
+}
+
 /// Holds the serializable members of [`FullOutput`]
 /// (since [`FullOutput`] is not serializable).
 #[derive(Clone, Serialize, Deserialize)]
@@ -85,7 +766,9 @@ struct FullOutput2 {
     /// The [`FullOutput::textures_delta`] field.
     pub textures_delta: TexturesDelta,
     /// The [`FullOutput::pixels_per_point`] field.
-    pub pixels_per_point: f32
+    pub pixels_per_point: f32,
+    /// The [`FullOutput::viewport_output`] field.
+    pub viewport_output: ViewportIdMap<ViewportOutput2>
 }
 
 impl From<FullOutput2> for FullOutput {
@@ -94,6 +777,7 @@ impl From<FullOutput2> for FullOutput {
             pixels_per_point: value.pixels_per_point,
             platform_output: value.platform_output,
             textures_delta: value.textures_delta,
+            viewport_output: value.viewport_output.into_iter().map(|(id, x)| (id, x.into())).collect(),
             ..Default::default()
         }
     }
@@ -104,13 +788,55 @@ impl From<FullOutput> for FullOutput2 {
         Self {
             pixels_per_point: value.pixels_per_point,
             platform_output: value.platform_output,
-            textures_delta: value.textures_delta
+            textures_delta: value.textures_delta,
+            viewport_output: value.viewport_output.into_iter().map(|(id, x)| (id, x.into())).collect()
+        }
+    }
+}
+
+/// Holds the serializable members of [`ViewportOutput`]
+/// (since [`ViewportOutput::viewport_ui_cb`] is not serializable).
+#[derive(Clone, Serialize, Deserialize)]
+struct ViewportOutput2 {
+    /// The [`ViewportOutput::parent`] field.
+    pub parent: ViewportId,
+    /// The [`ViewportOutput::class`] field.
+    pub class: ViewportClass,
+    /// The [`ViewportOutput::builder`] field.
+    pub builder: ViewportBuilder,
+    /// The [`ViewportOutput::commands`] field.
+    pub commands: Vec<ViewportCommand>,
+    /// The [`ViewportOutput::repaint_delay`] field.
+    pub repaint_delay: Duration
+}
+
+impl From<ViewportOutput2> for ViewportOutput {
+    fn from(value: ViewportOutput2) -> Self {
+        Self {
+            parent: value.parent,
+            class: value.class,
+            builder: value.builder,
+            viewport_ui_cb: None,
+            commands: value.commands,
+            repaint_delay: value.repaint_delay
+        }
+    }
+}
+
+impl From<ViewportOutput> for ViewportOutput2 {
+    fn from(value: ViewportOutput) -> Self {
+        Self {
+            parent: value.parent,
+            class: value.class,
+            builder: value.builder,
+            commands: value.commands,
+            repaint_delay: value.repaint_delay
         }
     }
 }
 
 /// An FFI-compatible version of [`TextureId`].
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(C)]
 struct FfiTextureId {
     /// The type of ID.
@@ -144,7 +870,7 @@ impl From<FfiTextureId> for TextureId {
 }
 
 /// Identifies a variant of [`TextureId`].
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(C)]
 enum TextureIdKind {
     /// [`TextureId::Managed`]
@@ -205,4 +931,175 @@ impl<T> Drop for FfiVec<T> {
             (self.on_free)(self)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Functions that can be run as tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Creates a mesh containing a single triangle.
+    fn triangle(texture_id: TextureId) -> Mesh {
+        let mut mesh = Mesh::default();
+        mesh.colored_vertex(pos2(0.0, 0.0), Color32::WHITE);
+        mesh.colored_vertex(pos2(1.0, 0.0), Color32::WHITE);
+        mesh.colored_vertex(pos2(0.0, 1.0), Color32::WHITE);
+        mesh.add_triangle(0, 1, 2);
+        mesh.texture_id = texture_id;
+        mesh
+    }
+
+    /// Ensures that adjacent meshes with the same texture and clip rectangle share a draw command,
+    /// and that their indices still refer to their own vertices.
+    #[test]
+    fn merges_contiguous_meshes() {
+        let clip_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(8.0, 8.0));
+        let mut ffi = EguiFfi::default();
+        ffi.set_mesh_layout(FfiMeshLayout::Contiguous);
+        ffi.set_meshes([
+            (clip_rect, triangle(TextureId::default())),
+            (clip_rect, triangle(TextureId::default())),
+            (clip_rect, triangle(TextureId::User(1))),
+            (Rect::EVERYTHING, triangle(TextureId::User(1)))
+        ], 1.0, Rect::EVERYTHING);
+
+        assert!(ffi.meshes().is_empty());
+        assert_eq!(ffi.vertices().len(), 12);
+        assert_eq!(ffi.draw_commands().iter().map(|x| (x.index_offset, x.index_count, x.vertex_offset)).collect::<Vec<_>>(),
+            [(0, 6, 0), (6, 3, 6), (9, 3, 9)]);
+        assert_eq!(&ffi.indices()[..6], [0, 1, 2, 3, 4, 5]);
+        assert_eq!(ffi.draw_commands()[1].texture_id(), TextureId::User(1));
+    }
+
+    /// Ensures that vertices and clip rectangles are converted to physical pixels, that clip
+    /// rectangles are rounded and clamped to the screen, and that 16-bit indices are emitted.
+    #[test]
+    fn converts_output_format() {
+        let mut ffi = EguiFfi::default();
+        ffi.set_mesh_layout(FfiMeshLayout::Contiguous);
+        ffi.set_output_format(FfiOutputFormat {
+            physical_pixels: true,
+            linear_colors: true,
+            u16_indices: true,
+            integer_scissor_rects: true
+        });
+
+        let screen_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 50.0));
+        let clip_rect = Rect::from_min_max(pos2(-3.0, 10.2), pos2(40.3, 80.0));
+        let mut mesh = triangle(TextureId::default());
+        mesh.vertices[1].color = Color32::from_rgba_premultiplied(128, 64, 0, 200);
+        ffi.set_meshes([(clip_rect, mesh)], 2.0, screen_rect);
+
+        assert!(ffi.indices().is_empty());
+        assert_eq!(ffi.indices_u16(), [0, 1, 2]);
+        assert_eq!(ffi.vertices()[0].color, Color32::WHITE);
+        assert_eq!(ffi.vertices()[1].pos, pos2(2.0, 0.0));
+        assert_eq!(ffi.vertices()[1].color, Color32::from_rgba_premultiplied(55, 13, 0, 200));
+        assert_eq!(ffi.draw_commands()[0].clip_rect, Rect::from_min_max(pos2(0.0, 20.0), pos2(81.0, 100.0)));
+    }
+
+    /// Ensures that separate meshes are given 16-bit indices, and divided if they have too many vertices.
+    #[test]
+    fn divides_separate_u16_meshes() {
+        let mut mesh = Mesh::default();
+        for i in 0..70000 {
+            mesh.colored_vertex(pos2(i as f32, 0.0), Color32::WHITE);
+        }
+        for i in (0..69998).step_by(3) {
+            mesh.add_triangle(i, i + 1, i + 2);
+        }
+
+        let mut ffi = EguiFfi::default();
+        ffi.set_output_format(FfiOutputFormat { u16_indices: true, ..Default::default() });
+        ffi.set_meshes([(Rect::EVERYTHING, mesh), (Rect::EVERYTHING, triangle(TextureId::User(1)))], 1.0, Rect::EVERYTHING);
+
+        let meshes = ffi.meshes_u16();
+        assert!(ffi.meshes().is_empty());
+        assert_eq!(meshes.len(), 3);
+        assert_eq!(meshes.iter().map(|(_, x)| x.indices.len()).sum::<usize>(), 69999 + 3);
+        assert!(meshes.iter().all(|(_, x)| x.vertices.len() <= u16::MAX as usize + 1));
+        assert_eq!(meshes[2].1.indices, [0, 1, 2]);
+        assert_eq!(meshes[2].1.texture_id, TextureId::User(1));
+    }
+
+    /// Ensures that host paint callbacks are kept in draw order and prevent the meshes
+    /// around them from being merged.
+    #[test]
+    fn keeps_paint_callbacks_in_order() {
+        let mesh = |mesh| ClippedPrimitive { clip_rect: Rect::EVERYTHING, primitive: Primitive::Mesh(mesh) };
+        let callback = |callback: Arc<dyn std::any::Any + Send + Sync>| ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Callback(PaintCallback { rect: Rect::from_min_max(pos2(1.0, 1.0), pos2(2.0, 2.0)), callback })
+        };
+
+        let mut ffi = EguiFfi::default();
+        ffi.set_mesh_layout(FfiMeshLayout::Contiguous);
+        ffi.set_primitives([
+            mesh(triangle(TextureId::default())),
+            callback(Arc::new(FfiPaintCallback { id: 7 })),
+            callback(Arc::new(())),
+            mesh(triangle(TextureId::default()))
+        ], 1.0, Rect::EVERYTHING);
+
+        assert_eq!(ffi.draw_commands().len(), 2);
+        assert_eq!(ffi.paint_callbacks().iter().map(|x| (x.id, x.draw_index)).collect::<Vec<_>>(), [(7, 1)]);
+    }
+
+    /// Ensures that each viewport keeps its own meshes, and that the output of each
+    /// viewport survives serialization.
+    #[test]
+    fn keeps_viewport_output() {
+        let child = ViewportId::from_hash_of("child");
+        let mesh = |mesh| ClippedPrimitive { clip_rect: Rect::EVERYTHING, primitive: Primitive::Mesh(mesh) };
+
+        let mut ffi = EguiFfi::default();
+        ffi.set_meshes([(Rect::EVERYTHING, triangle(TextureId::default()))], 1.0, Rect::EVERYTHING);
+        ffi.set_viewport_primitives([(child, vec![mesh(triangle(TextureId::default())), mesh(triangle(TextureId::User(1)))], 2.0, Rect::EVERYTHING)]);
+
+        assert_eq!(ffi.meshes().len(), 1);
+        assert_eq!(ffi.viewport_frame(child).map(|x| x.meshes().len()), Some(2));
+        assert_eq!(ffi.viewport_frames().map(|(id, _)| id).collect::<Vec<_>>(), [ViewportId::ROOT, child]);
+
+        let mut full_output = FullOutput::default();
+        full_output.viewport_output.insert(child, ViewportOutput {
+            parent: ViewportId::ROOT,
+            class: ViewportClass::Deferred,
+            builder: ViewportBuilder::default().with_title("Child"),
+            viewport_ui_cb: Some(Arc::new(|_| {})),
+            commands: vec![ViewportCommand::Focus],
+            repaint_delay: Duration::from_secs(1)
+        });
+        ffi.set_full_output(full_output);
+
+        let output = ffi.full_output().viewport_output.remove(&child).expect("Failed to get viewport output");
+        assert!(output.class == ViewportClass::Deferred);
+        assert!(output.viewport_ui_cb.is_none());
+        assert_eq!(output.builder.title.as_deref(), Some("Child"));
+        assert_eq!(output.commands, [ViewportCommand::Focus]);
+        assert_eq!(output.repaint_delay, Duration::from_secs(1));
+    }
+
+    /// Ensures that nested shapes are flattened, that paths colored by callbacks are
+    /// tessellated, and that only host paint callbacks are kept.
+    #[test]
+    fn converts_shapes() {
+        let clip_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(8.0, 8.0));
+        let shape = |shape| ClippedShape { clip_rect, shape };
+
+        let mut ffi = EguiFfi::default();
+        ffi.set_shapes([
+            shape(Shape::Vec(vec![Shape::Noop, Shape::circle_filled(pos2(1.0, 1.0), 1.0, Color32::WHITE)])),
+            shape(Shape::line(vec![pos2(0.0, 0.0), pos2(4.0, 4.0)], PathStroke::new_uv(1.0_f32, |_, _| Color32::RED))),
+            shape(Shape::Callback(PaintCallback { rect: clip_rect, callback: Arc::new(FfiPaintCallback { id: 3 }) })),
+            shape(Shape::Callback(PaintCallback { rect: clip_rect, callback: Arc::new(()) }))
+        ], 1.0);
+
+        let shapes = ffi.shapes();
+        assert_eq!(shapes.len(), 3);
+        assert!(matches!(shapes[0].shape, FfiShape::Circle(_)));
+        assert!(matches!(&shapes[1].shape, FfiShape::Mesh(x) if !x.is_empty()));
+        assert_eq!(shapes[2].shape, FfiShape::Callback { id: 3, rect: clip_rect });
+        assert!(ffi.meshes().is_empty());
+    }
+}